@@ -0,0 +1,291 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::{LocationInfo, AIR_QUALITY_API, llm::Tool, weather::{OpenMeteoError, format::format_value}};
+
+const CURRENT_VARIABLES: &str = "european_aqi,us_aqi,pm2_5,pm10,ozone,nitrogen_dioxide,alder_pollen,birch_pollen,grass_pollen,mugwort_pollen,olive_pollen,ragweed_pollen";
+
+/// Open-Meteo `/v1/air-quality` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirQualityData {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: Option<String>,
+    pub current: AirQualityCurrent,
+    #[serde(default)]
+    pub current_units: BTreeMap<String, String>,
+}
+
+/// Current pollutant concentrations (µg/m³), indices, and pollen counts
+/// (grains/m³). Pollen is only modelled for Europe and is `None` elsewhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AirQualityCurrent {
+    pub time: String,
+    pub european_aqi: Option<f64>,
+    pub us_aqi: Option<f64>,
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+    pub ozone: Option<f64>,
+    pub nitrogen_dioxide: Option<f64>,
+    pub alder_pollen: Option<f64>,
+    pub birch_pollen: Option<f64>,
+    pub grass_pollen: Option<f64>,
+    pub mugwort_pollen: Option<f64>,
+    pub olive_pollen: Option<f64>,
+    pub ragweed_pollen: Option<f64>,
+}
+
+impl AirQualityData {
+    pub fn current_unit(&self, variable: &str) -> &str {
+        self.current_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// US EPA Air Quality Index category.
+pub fn us_aqi_category(aqi: f64) -> &'static str {
+    match aqi {
+        aqi if aqi <= 50.0 => "good",
+        aqi if aqi <= 100.0 => "moderate",
+        aqi if aqi <= 150.0 => "unhealthy for sensitive groups",
+        aqi if aqi <= 200.0 => "unhealthy",
+        aqi if aqi <= 300.0 => "very unhealthy",
+        _ => "hazardous",
+    }
+}
+
+/// European Air Quality Index category.
+pub fn european_aqi_category(aqi: f64) -> &'static str {
+    match aqi {
+        aqi if aqi <= 20.0 => "good",
+        aqi if aqi <= 40.0 => "fair",
+        aqi if aqi <= 60.0 => "moderate",
+        aqi if aqi <= 80.0 => "poor",
+        aqi if aqi <= 100.0 => "very poor",
+        _ => "extremely poor",
+    }
+}
+
+/// Category of a single pollutant concentration using the European AQI
+/// bands (µg/m³). Returns `None` for pollutants without bands.
+pub fn pollutant_category(pollutant: &str, value: f64) -> Option<&'static str> {
+    const CATEGORIES: [&str; 6] = ["good", "fair", "moderate", "poor", "very poor", "extremely poor"];
+    let bands: [f64; 5] = match pollutant {
+        "pm2_5" => [10.0, 20.0, 25.0, 50.0, 75.0],
+        "pm10" => [20.0, 40.0, 50.0, 100.0, 150.0],
+        "ozone" => [50.0, 100.0, 130.0, 240.0, 380.0],
+        "nitrogen_dioxide" => [40.0, 90.0, 120.0, 230.0, 340.0],
+        _ => return None,
+    };
+    let index = bands.iter().take_while(|&&upper| value > upper).count();
+    Some(CATEGORIES[index])
+}
+
+/// Rough pollen load category from grains/m³.
+pub fn pollen_category(grains: f64) -> &'static str {
+    match grains {
+        grains if grains < 1.0 => "none",
+        grains if grains < 10.0 => "low",
+        grains if grains < 50.0 => "moderate",
+        grains if grains < 200.0 => "high",
+        _ => "very high",
+    }
+}
+
+pub struct AirQuality {
+    location_info: LocationInfo,
+    base_url: String,
+}
+
+impl AirQuality {
+    pub fn new(location_info: LocationInfo) -> Self {
+        Self { location_info, base_url: AIR_QUALITY_API.to_string() }
+    }
+
+    /// Fetches pollutant and AQI readings from a different air-quality
+    /// server, such as a self-hosted Open-Meteo.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub async fn get_air_quality_data(&self) -> Result<AirQualityData> {
+        let url = format!(
+            "{base}?latitude={lat}&longitude={lon}&current={variables}&timezone=auto",
+            base = self.base_url,
+            lat = self.location_info.latitude,
+            lon = self.location_info.longitude,
+            variables = CURRENT_VARIABLES
+        );
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("User-Agent", "multi-tool-agent/1.0")
+            .send()
+            .await
+            .context("Failed to fetch air quality data")?;
+
+        if !response.status().is_success() {
+            let error: OpenMeteoError = response
+                .json()
+                .await
+                .context("Failed to read air quality error response")?;
+            anyhow::bail!("Open-Meteo rejected the air quality request: {}", error.reason);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse air quality response")
+    }
+
+    pub async fn get_air_quality(&self) -> Result<String> {
+        let data = self.get_air_quality_data().await?;
+        Ok(render_air_quality(&self.location_info, &data))
+    }
+}
+
+/// Indices and pollutants with their health category, then any pollen types
+/// that are actually present.
+pub fn render_air_quality(location_info: &LocationInfo, data: &AirQualityData) -> String {
+    let current = &data.current;
+    let mut output = format!(
+        "Air quality for {} ({}):\n",
+        location_info.name,
+        current.time.replace('T', " ")
+    );
+
+    if let Some(aqi) = current.us_aqi {
+        output.push_str(&format!("- US AQI: {} ({})\n", aqi, us_aqi_category(aqi)));
+    }
+    if let Some(aqi) = current.european_aqi {
+        output.push_str(&format!("- European AQI: {} ({})\n", aqi, european_aqi_category(aqi)));
+    }
+
+    let pollutants = [
+        ("PM2.5", "pm2_5", current.pm2_5),
+        ("PM10", "pm10", current.pm10),
+        ("Ozone", "ozone", current.ozone),
+        ("NO2", "nitrogen_dioxide", current.nitrogen_dioxide),
+    ];
+    for (label, variable, value) in pollutants {
+        if let Some(value) = value {
+            let category = pollutant_category(variable, value).unwrap_or("n/a");
+            output.push_str(&format!(
+                "- {}: {} ({})\n",
+                label,
+                format_value(Some(value), data.current_unit(variable)),
+                category
+            ));
+        }
+    }
+
+    let pollen: Vec<String> = [
+        ("alder", "alder_pollen", current.alder_pollen),
+        ("birch", "birch_pollen", current.birch_pollen),
+        ("grass", "grass_pollen", current.grass_pollen),
+        ("mugwort", "mugwort_pollen", current.mugwort_pollen),
+        ("olive", "olive_pollen", current.olive_pollen),
+        ("ragweed", "ragweed_pollen", current.ragweed_pollen),
+    ]
+    .into_iter()
+    .filter_map(|(label, variable, value)| value.map(|value| (label, variable, value)))
+    .filter(|&(_, _, value)| value >= 1.0)
+    .map(|(label, variable, value)| {
+        format!(
+            "{} {} ({})",
+            label,
+            format_value(Some(value), data.current_unit(variable)),
+            pollen_category(value)
+        )
+    })
+    .collect();
+
+    let has_pollen_data = current.birch_pollen.is_some() || current.grass_pollen.is_some();
+    match (pollen.is_empty(), has_pollen_data) {
+        (false, _) => output.push_str(&format!("- Pollen: {}\n", pollen.join(", "))),
+        (true, true) => output.push_str("- Pollen: none detected\n"),
+        (true, false) => output.push_str("- Pollen: not available for this region\n"),
+    }
+
+    output
+}
+
+#[async_trait]
+impl Tool for AirQuality {
+    fn name(&self) -> &str {
+        "get_air_quality"
+    }
+
+    fn description(&self) -> &str {
+        "Get current air quality (US and European AQI, PM2.5, PM10, ozone, NO2) and pollen levels with health categories"
+    }
+
+    async fn call(&self, _args: serde_json::Value) -> Result<String> {
+        self.get_air_quality().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{StubServer, location};
+
+    #[test]
+    fn categorizes_pollutants_on_band_edges() {
+        assert_eq!(pollutant_category("pm2_5", 10.0), Some("good"));
+        assert_eq!(pollutant_category("pm2_5", 10.1), Some("fair"));
+        assert_eq!(pollutant_category("ozone", 400.0), Some("extremely poor"));
+        assert_eq!(pollutant_category("carbon_monoxide", 200.0), None);
+        assert_eq!(us_aqi_category(101.0), "unhealthy for sensitive groups");
+        assert_eq!(european_aqi_category(60.0), "moderate");
+        assert_eq!(pollen_category(0.5), "none");
+    }
+
+    #[tokio::test]
+    async fn renders_the_current_air_quality_from_the_endpoint() {
+        let server = StubServer::start(vec![(200, r#"{
+            "latitude": 52.5, "longitude": 13.4, "timezone": "Europe/Berlin",
+            "current_units": {"pm2_5": "μg/m³", "pm10": "μg/m³", "birch_pollen": "grains/m³", "grass_pollen": "grains/m³"},
+            "current": {"time": "2025-04-10T12:00", "european_aqi": 45, "us_aqi": 72, "pm2_5": 22.5, "pm10": 30.1,
+                        "birch_pollen": 120.0, "grass_pollen": 0.2}
+        }"#)])
+        .await;
+        let air_quality = AirQuality::new(location("Berlin", 52.5, 13.4)).with_base_url(format!("{}/v1/air-quality", server.url));
+
+        let output = air_quality.get_air_quality().await.unwrap();
+
+        assert!(output.starts_with("Air quality for Berlin (2025-04-10 12:00):"), "{}", output);
+        assert!(output.contains("- US AQI: 72 (moderate)"), "{}", output);
+        assert!(output.contains("- European AQI: 45 (moderate)"), "{}", output);
+        assert!(output.contains("- PM2.5: 22.5 μg/m³ (moderate)"), "{}", output);
+        assert!(output.contains("- Pollen: birch 120 grains/m³ (high)"), "{}", output);
+        let path = &server.requests()[0].path;
+        assert!(path.starts_with("/v1/air-quality?latitude=52.5&longitude=13.4&current=european_aqi"), "{}", path);
+    }
+
+    #[tokio::test]
+    async fn reports_missing_pollen_outside_europe() {
+        let server = StubServer::start(vec![(200, r#"{
+            "latitude": 35.7, "longitude": 139.7, "timezone": "Asia/Tokyo",
+            "current": {"time": "2025-04-10T12:00", "us_aqi": 30}
+        }"#)])
+        .await;
+        let air_quality = AirQuality::new(location("Tokyo", 35.7, 139.7)).with_base_url(&server.url);
+
+        let output = air_quality.get_air_quality().await.unwrap();
+
+        assert!(output.contains("- Pollen: not available for this region"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn reports_open_meteo_errors() {
+        let server = StubServer::start(vec![(400, r#"{"error": true, "reason": "Latitude must be in range of -90 to 90°."}"#)]).await;
+        let air_quality = AirQuality::new(location("Nowhere", 95.0, 0.0)).with_base_url(&server.url);
+
+        let error = air_quality.get_air_quality().await.unwrap_err();
+
+        assert!(error.to_string().contains("Latitude must be in range"), "{:#}", error);
+    }
+}
@@ -1,36 +1,222 @@
-use anyhow::{Result, Context};
-use crate::{LocationInfo, WEATHER_API};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use crate::{
+    LocationInfo,
+    llm::Tool,
+    units::Units,
+    weather::{ForecastDetail, ForecastRequest, ForecastVariable, Weather},
+};
 
+pub mod cap;
+pub mod rules;
+
+pub use cap::FeedConfig;
+pub use rules::{AlertRules, ThresholdRule, ThunderstormRule};
+
+const DEFAULT_ALERT_DAYS: u8 = 3;
+/// Source attributed to alerts computed by the threshold rules.
+pub const FORECAST_SOURCE: &str = "Open-Meteo forecast";
+
+/// How serious an alert is, ordered from least to most severe. Uses the CAP
+/// severity levels so threshold and published alerts compare directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Minor => "minor",
+            Self::Moderate => "moderate",
+            Self::Severe => "severe",
+            Self::Extreme => "extreme",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertKind {
+    Heat,
+    Frost,
+    Wind,
+    Precipitation,
+    Thunderstorm,
+    /// Warning published by a weather service feed; the event name is in the
+    /// message.
+    Official,
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Heat => "Heat",
+            Self::Frost => "Frost",
+            Self::Wind => "High wind",
+            Self::Precipitation => "Heavy precipitation",
+            Self::Thunderstorm => "Thunderstorm",
+            Self::Official => "Official warning",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One alert covering `start..=end`. Threshold alerts use local ISO dates,
+/// published warnings the ISO timestamps from their feed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub severity: Severity,
+    pub kind: AlertKind,
+    pub start: String,
+    pub end: String,
+    pub message: String,
+    /// Where the alert came from: the forecast rules or a feed name.
+    pub source: String,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Timestamps are cut to the minute: `2024-06-01T15:00:00-05:00` -> `2024-06-01 15:00`.
+        let short = |time: &str| time.get(..16).unwrap_or(time).replace('T', " ");
+        let period = if self.start == self.end {
+            short(&self.start)
+        } else {
+            format!("{} to {}", short(&self.start), short(&self.end))
+        };
+        write!(f, "[{}] {} {}: {} ({})", self.severity, self.kind, period, self.message, self.source)
+    }
+}
+
+/// `[alerts]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// Days of forecast to check, 1 to 16.
+    pub forecast_days: u8,
+    #[serde(flatten)]
+    pub rules: AlertRules,
+    /// Published warning feeds merged with the threshold alerts.
+    pub feeds: Vec<FeedConfig>,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self { forecast_days: DEFAULT_ALERT_DAYS, rules: AlertRules::default(), feeds: Vec::new() }
+    }
+}
+
+/// Alerts derived from the forecast by the configured threshold rules, plus
+/// any warnings from the configured CAP/Atom feeds.
 pub struct Alerts {
     location_info: LocationInfo,
+    units: Units,
+    config: AlertsConfig,
 }
 
 impl Alerts {
     pub fn new(location_info: LocationInfo) -> Self {
-        Self { location_info }
+        Self { location_info, units: Units::default(), config: AlertsConfig::default() }
+    }
+
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    pub fn with_config(mut self, config: AlertsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn forecast_request(&self) -> ForecastRequest {
+        ForecastRequest {
+            detail: ForecastDetail::Daily,
+            variables: vec![ForecastVariable::WindGusts],
+            ..ForecastRequest::days(self.config.forecast_days)
+        }
+    }
+
+    pub async fn get_alert_list(&self) -> Result<Vec<Alert>> {
+        let request = self.forecast_request();
+        let forecast = Weather::new(self.location_info.clone())
+            .with_units(self.units)
+            .get_forecast_with(&request)
+            .await?;
+
+        let mut alerts = self.config.rules.evaluate(&forecast, &self.units);
+
+        // A feed that is down should not hide the other alerts.
+        let feeds = self.config.feeds.iter().map(|feed| cap::fetch_feed(feed, &self.location_info));
+        for (feed, result) in self.config.feeds.iter().zip(future::join_all(feeds).await) {
+            match result {
+                Ok(feed_alerts) => alerts.extend(feed_alerts),
+                Err(e) => tracing::warn!("Skipping {} alerts: {:#}", feed.name, e),
+            }
+        }
+
+        alerts.sort_by(|a, b| a.start.cmp(&b.start).then(b.severity.cmp(&a.severity)));
+        Ok(alerts)
     }
 
     pub async fn get_alerts(&self) -> Result<String> {
-        let url = format!(
-            "{base}?latitude={lat}&longitude={lon}&current_weather=true&daily=temperature_2m_max,temperature_2m_min,precipitation_sum,wind_speed_10m_max&alerts=true&timezone=auto",
-            base = WEATHER_API,
-            lat = self.location_info.latitude,
-            lon = self.location_info.longitude
-        );
-        
-        let client = reqwest::Client::new();
-        let response = client
-            .get(&url)
-            .header("User-Agent", "multi-tool-agent/1.0")
-            .send()
-            .await
-            .context("Failed to fetch alerts data")?;
-        
-        let alerts_data = response
-            .text()
-            .await
-            .context("Failed to read alerts response")?;
-        
-        Ok(format!("Weather alerts and warnings for {}: {}", self.location_info.name, alerts_data))
-    }
-} 
\ No newline at end of file
+        let alerts = self.get_alert_list().await?;
+        // Report the horizon that was fetched, not the configured value.
+        let days = self.forecast_request().forecast_days();
+        Ok(render_alerts(&self.location_info, days, &alerts))
+    }
+}
+
+pub fn render_alerts(location_info: &LocationInfo, days: u8, alerts: &[Alert]) -> String {
+    if alerts.is_empty() {
+        return format!("No weather alerts for {} in the next {} days", location_info.name, days);
+    }
+
+    let mut output = format!("Weather alerts for {} (next {} days):\n", location_info.name, days);
+    for alert in alerts {
+        output.push_str(&format!("- {}\n", alert));
+    }
+    output
+}
+
+#[async_trait]
+impl Tool for Alerts {
+    fn name(&self) -> &str {
+        "get_alerts"
+    }
+
+    fn description(&self) -> &str {
+        "Get weather alerts for the coming days: heat, frost, high wind, heavy precipitation and thunderstorms \
+        from the forecast, plus official warnings from weather services"
+    }
+
+    async fn call(&self, _args: serde_json::Value) -> Result<String> {
+        self.get_alerts().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::location;
+
+    #[test]
+    fn reports_the_clamped_forecast_horizon() {
+        let config = AlertsConfig { forecast_days: 30, ..AlertsConfig::default() };
+        let alerts = Alerts::new(location("Tokyo", 35.68, 139.69)).with_config(config);
+
+        let days = alerts.forecast_request().forecast_days();
+        let output = render_alerts(&alerts.location_info, days, &[]);
+
+        assert_eq!(days, 16);
+        assert_eq!(output, "No weather alerts for Tokyo in the next 16 days");
+    }
+}
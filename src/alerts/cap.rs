@@ -0,0 +1,362 @@
+use anyhow::{Result, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{LocationInfo, feeds::fetch_document};
+use super::{Alert, AlertKind, Severity};
+
+/// A published warning feed, from the `[[alerts.feeds]]` config entries.
+///
+/// `url` may contain `{lat}` and `{lon}` placeholders, and may point at a
+/// local file (`file://` or a plain path) instead of an HTTP endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedConfig {
+    /// Shown as the source of each alert, e.g. "NWS".
+    pub name: String,
+    pub url: String,
+    /// Keep warnings without a polygon. Set this for feeds that are already
+    /// filtered by location (like the NWS `?point=` query); otherwise such
+    /// warnings are only kept when their area names the location.
+    #[serde(default)]
+    pub keep_unlocated: bool,
+}
+
+impl FeedConfig {
+    /// NWS active alerts for the location's point (US only).
+    pub fn nws() -> Self {
+        Self {
+            name: "NWS".to_string(),
+            url: "https://api.weather.gov/alerts/active.atom?point={lat},{lon}".to_string(),
+            keep_unlocated: true,
+        }
+    }
+
+    /// MeteoAlarm warnings for one European country, e.g. "germany".
+    ///
+    /// The legacy Atom feeds carry no polygons, only region names such as
+    /// "Oberbayern". Warnings are matched against the place name and its
+    /// first-level region (state, province), so a warning issued for a
+    /// district that names neither is missed.
+    pub fn meteoalarm(country: &str) -> Self {
+        Self {
+            name: "MeteoAlarm".to_string(),
+            url: format!("https://feeds.meteoalarm.org/feeds/meteoalarm-legacy-atom-{}", country.to_lowercase()),
+            keep_unlocated: false,
+        }
+    }
+
+    fn resolved_url(&self, location_info: &LocationInfo) -> String {
+        self.url
+            .replace("{lat}", &format!("{:.4}", location_info.latitude))
+            .replace("{lon}", &format!("{:.4}", location_info.longitude))
+    }
+}
+
+/// One warning read from a CAP document or an Atom entry with CAP fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapAlert {
+    pub event: String,
+    pub headline: Option<String>,
+    pub severity: Severity,
+    pub onset: Option<String>,
+    pub expires: Option<String>,
+    pub area: String,
+    /// Polygons as `(latitude, longitude)` rings.
+    pub polygons: Vec<Vec<(f64, f64)>>,
+}
+
+impl CapAlert {
+    /// Whether the warning applies at the given point. Warnings without
+    /// geometry fall back to matching the area description against the
+    /// place name and its region.
+    pub fn applies_to(&self, location_info: &LocationInfo, keep_unlocated: bool) -> bool {
+        if self.polygons.is_empty() {
+            let area = self.area.to_lowercase();
+            return keep_unlocated
+                || std::iter::once(&location_info.name)
+                    .chain(location_info.admin1.as_ref())
+                    .filter(|name| !name.is_empty())
+                    .any(|name| area.contains(&name.to_lowercase()));
+        }
+        self.polygons
+            .iter()
+            .any(|polygon| contains(polygon, location_info.latitude, location_info.longitude))
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires
+            .as_deref()
+            .and_then(|expires| DateTime::parse_from_rfc3339(expires).ok())
+            .is_some_and(|expires| expires < now)
+    }
+
+    pub fn into_alert(self, source: &str) -> Alert {
+        let message = match &self.headline {
+            Some(headline) if !headline.is_empty() => format!("{}: {}", self.event, headline),
+            _ => format!("{} for {}", self.event, self.area),
+        };
+        let start = self.onset.unwrap_or_default();
+        Alert {
+            severity: self.severity,
+            kind: AlertKind::Official,
+            end: self.expires.unwrap_or_else(|| start.clone()),
+            start,
+            message,
+            source: source.to_string(),
+        }
+    }
+}
+
+/// Fetches a feed and returns the current warnings that cover the location.
+pub async fn fetch_feed(feed: &FeedConfig, location_info: &LocationInfo) -> Result<Vec<Alert>> {
+    let url = feed.resolved_url(location_info);
+    let xml = fetch_document(&url, "application/atom+xml, application/cap+xml, application/xml")
+        .await
+        .with_context(|| format!("Failed to load {} feed", feed.name))?;
+
+    let now = Utc::now();
+    let alerts = parse_feed(&xml)
+        .with_context(|| format!("Failed to parse {} feed", feed.name))?
+        .into_iter()
+        .filter(|alert| !alert.is_expired(now) && alert.applies_to(location_info, feed.keep_unlocated))
+        .map(|alert| alert.into_alert(&feed.name))
+        .collect();
+    Ok(alerts)
+}
+
+/// Parses either an Atom feed whose entries carry `cap:` fields or a single
+/// CAP `<alert>` document. Elements are matched by local name, so the CAP
+/// namespace prefix used by a feed does not matter.
+pub fn parse_feed(xml: &str) -> Result<Vec<CapAlert>> {
+    let document = roxmltree::Document::parse(xml).context("Invalid XML")?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "feed" => Ok(root.children().filter(|node| node.has_tag_name("entry")).map(parse_atom_entry).collect()),
+        "alert" => Ok(parse_cap_alert(root)),
+        other => anyhow::bail!("Expected an Atom feed or CAP alert, found <{}>", other),
+    }
+}
+
+fn parse_atom_entry(entry: roxmltree::Node) -> CapAlert {
+    let event = child_text(entry, "event")
+        .or_else(|| child_text(entry, "title"))
+        .unwrap_or_else(|| "Weather warning".to_string());
+    CapAlert {
+        headline: child_text(entry, "headline")
+            .or_else(|| child_text(entry, "title"))
+            .filter(|headline| *headline != event),
+        event,
+        severity: parse_severity(child_text(entry, "severity").as_deref()),
+        onset: child_text(entry, "onset").or_else(|| child_text(entry, "effective")),
+        expires: child_text(entry, "expires"),
+        area: child_text(entry, "areaDesc").unwrap_or_default(),
+        polygons: entry
+            .children()
+            .filter(|node| node.has_tag_name("polygon"))
+            .filter_map(|node| parse_polygon(node.text().unwrap_or_default()))
+            .collect(),
+    }
+}
+
+/// A CAP alert may carry several `<info>` blocks, one per language; the
+/// English one is preferred.
+fn parse_cap_alert(alert: roxmltree::Node) -> Vec<CapAlert> {
+    let infos: Vec<roxmltree::Node> = alert.children().filter(|node| node.has_tag_name("info")).collect();
+    let info = infos
+        .iter()
+        .find(|info| child_text(**info, "language").is_some_and(|language| language.starts_with("en")))
+        .or_else(|| infos.first());
+    let Some(&info) = info else {
+        return Vec::new();
+    };
+
+    let areas: Vec<roxmltree::Node> = info.children().filter(|node| node.has_tag_name("area")).collect();
+    vec![CapAlert {
+        event: child_text(info, "event").unwrap_or_else(|| "Weather warning".to_string()),
+        headline: child_text(info, "headline"),
+        severity: parse_severity(child_text(info, "severity").as_deref()),
+        onset: child_text(info, "onset").or_else(|| child_text(info, "effective")),
+        expires: child_text(info, "expires"),
+        area: areas
+            .iter()
+            .filter_map(|area| child_text(*area, "areaDesc"))
+            .collect::<Vec<_>>()
+            .join("; "),
+        polygons: areas
+            .iter()
+            .flat_map(|area| area.children().filter(|node| node.has_tag_name("polygon")))
+            .filter_map(|node| parse_polygon(node.text().unwrap_or_default()))
+            .collect(),
+    }]
+}
+
+fn parse_severity(severity: Option<&str>) -> Severity {
+    match severity.map(str::to_lowercase).as_deref() {
+        Some("extreme") => Severity::Extreme,
+        Some("severe") => Severity::Severe,
+        Some("moderate") => Severity::Moderate,
+        _ => Severity::Minor,
+    }
+}
+
+/// CAP polygons are whitespace-separated `lat,lon` pairs; at least three
+/// points are needed.
+fn parse_polygon(text: &str) -> Option<Vec<(f64, f64)>> {
+    let points: Vec<(f64, f64)> = text
+        .split_whitespace()
+        .map(|pair| {
+            let (lat, lon) = pair.split_once(',')?;
+            Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+        })
+        .collect::<Option<_>>()?;
+    (points.len() >= 3).then_some(points)
+}
+
+/// Ray-casting point-in-polygon test on `(lat, lon)` coordinates.
+fn contains(polygon: &[(f64, f64)], lat: f64, lon: f64) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &point in polygon {
+        let ((lat_a, lon_a), (lat_b, lon_b)) = (point, previous);
+        if (lat_a > lat) != (lat_b > lat) && lon < (lon_b - lon_a) * (lat - lat_a) / (lat_b - lat_a) + lon_a {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{fixture, location};
+
+    fn unlocated(area: &str) -> CapAlert {
+        CapAlert {
+            event: "Wind warning".to_string(),
+            headline: None,
+            severity: Severity::Moderate,
+            onset: None,
+            expires: None,
+            area: area.to_string(),
+            polygons: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_unlocated_warnings_by_place_or_region_name() {
+        let mut munich = location("Munich", 48.14, 11.58);
+        munich.admin1 = Some("Bavaria".to_string());
+
+        assert!(unlocated("City of Munich").applies_to(&munich, false));
+        assert!(unlocated("Southern Bavaria").applies_to(&munich, false));
+        assert!(!unlocated("Oberbayern").applies_to(&munich, false));
+        assert!(unlocated("Oberbayern").applies_to(&munich, true));
+    }
+
+    fn feed(name: &str, keep_unlocated: bool) -> FeedConfig {
+        FeedConfig { name: "Test".to_string(), url: fixture(name), keep_unlocated }
+    }
+
+    #[test]
+    fn maps_cap_severities() {
+        assert_eq!(parse_severity(Some("Extreme")), Severity::Extreme);
+        assert_eq!(parse_severity(Some("severe")), Severity::Severe);
+        assert_eq!(parse_severity(Some("MODERATE")), Severity::Moderate);
+        assert_eq!(parse_severity(Some("Minor")), Severity::Minor);
+        assert_eq!(parse_severity(Some("Unknown")), Severity::Minor);
+        assert_eq!(parse_severity(None), Severity::Minor);
+    }
+
+    #[tokio::test]
+    async fn prefers_the_english_info_block() {
+        let london = location("London", 51.51, -0.13);
+
+        let alerts = fetch_feed(&feed("cap_alert.xml", false), &london).await.unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].message, "Wind: Gusts up to 110 km/h");
+        assert_eq!(alerts[0].severity, Severity::Severe);
+        assert_eq!(alerts[0].kind, AlertKind::Official);
+        assert_eq!(alerts[0].source, "Test");
+        assert_eq!(alerts[0].start, "2025-06-15T12:00:00+00:00");
+    }
+
+    #[test]
+    fn joins_areas_and_collects_their_polygons() {
+        let xml = std::fs::read_to_string(fixture("cap_alert.xml").trim_start_matches("file://")).unwrap();
+
+        let alerts = parse_feed(&xml).unwrap();
+
+        assert_eq!(alerts[0].area, "Greater London; Kent");
+        assert_eq!(alerts[0].polygons.len(), 1);
+        assert_eq!(alerts[0].polygons[0][2], (51.7, 0.3));
+    }
+
+    #[tokio::test]
+    async fn polygons_spanning_the_prime_meridian_match_both_sides() {
+        let cap = feed("cap_alert.xml", false);
+
+        let west = fetch_feed(&cap, &location("Westminster", 51.50, -0.14)).await.unwrap();
+        let east = fetch_feed(&cap, &location("Greenwich", 51.48, 0.01)).await.unwrap();
+        let outside = fetch_feed(&cap, &location("Paris", 48.86, 2.35)).await.unwrap();
+
+        assert_eq!(west.len(), 1);
+        assert_eq!(east.len(), 1);
+        assert!(outside.is_empty(), "{:?}", outside);
+    }
+
+    #[tokio::test]
+    async fn keeps_current_warnings_inside_the_polygon() {
+        let phoenix = location("Phoenix", 33.45, -112.07);
+
+        let alerts = fetch_feed(&feed("nws_alerts.atom", false), &phoenix).await.unwrap();
+
+        let messages: Vec<&str> = alerts.iter().map(|alert| alert.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["Excessive Heat Warning: Excessive Heat Warning issued June 15 at 4:00AM MST until June 17 at 8:00PM MST by NWS Phoenix AZ"]
+        );
+        assert_eq!(alerts[0].severity, Severity::Severe);
+        assert_eq!(alerts[0].start, "2025-06-15T04:00:00-07:00");
+    }
+
+    #[tokio::test]
+    async fn keep_unlocated_keeps_warnings_without_polygons() {
+        let phoenix = location("Phoenix", 33.45, -112.07);
+
+        let alerts = fetch_feed(&feed("nws_alerts.atom", true), &phoenix).await.unwrap();
+
+        let dust = alerts.iter().find(|alert| alert.message.starts_with("Dust Advisory")).expect("dust advisory kept");
+        assert_eq!(dust.severity, Severity::Minor);
+        assert_eq!(alerts.len(), 2, "{:?}", alerts);
+    }
+
+    #[test]
+    fn expiry_is_compared_with_the_current_time() {
+        let mut alert = unlocated("Central Phoenix");
+        alert.expires = Some("2025-06-14T18:00:00-07:00".to_string());
+        let before = DateTime::parse_from_rfc3339("2025-06-14T12:00:00-07:00").unwrap().with_timezone(&Utc);
+        let after = DateTime::parse_from_rfc3339("2025-06-14T19:00:00-07:00").unwrap().with_timezone(&Utc);
+
+        assert!(!alert.is_expired(before));
+        assert!(alert.is_expired(after));
+        alert.expires = None;
+        assert!(!alert.is_expired(after));
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let error = parse_feed("<rss><channel/></rss>").unwrap_err();
+
+        assert!(error.to_string().contains("<rss>"), "{}", error);
+    }
+}
@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    units::Units,
+    weather::{DailyForecast, Forecast, describe_weather_code, format::{format_value, value_at}},
+};
+use super::{Alert, AlertKind, Severity, FORECAST_SOURCE};
+
+/// Two-level threshold. Values are metric (°C, km/h, mm) in the config file
+/// and converted to the forecast's units before comparing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ThresholdRule {
+    pub enabled: bool,
+    pub moderate: f64,
+    pub severe: f64,
+}
+
+/// A threshold table as written in the config file. Fields left out keep
+/// the built-in rule's values, so `severe = 40.0` alone is enough.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThresholdOverride {
+    enabled: Option<bool>,
+    moderate: Option<f64>,
+    severe: Option<f64>,
+}
+
+impl ThresholdOverride {
+    fn apply(self, rule: ThresholdRule) -> ThresholdRule {
+        ThresholdRule {
+            enabled: self.enabled.unwrap_or(rule.enabled),
+            moderate: self.moderate.unwrap_or(rule.moderate),
+            severe: self.severe.unwrap_or(rule.severe),
+        }
+    }
+}
+
+impl ThresholdRule {
+    pub fn new(moderate: f64, severe: f64) -> Self {
+        Self { enabled: true, moderate, severe }
+    }
+
+    /// Severity reached by `value`. `above` selects the direction: heat, wind
+    /// and rain trigger at or above the threshold, frost at or below.
+    fn severity(&self, value: f64, above: bool, convert: impl Fn(f64) -> f64) -> Option<Severity> {
+        let reached = |threshold: f64| if above { value >= convert(threshold) } else { value <= convert(threshold) };
+        if !self.enabled {
+            None
+        } else if reached(self.severe) {
+            Some(Severity::Severe)
+        } else if reached(self.moderate) {
+            Some(Severity::Moderate)
+        } else {
+            None
+        }
+    }
+}
+
+/// WMO weather codes that raise a thunderstorm alert.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThunderstormRule {
+    pub enabled: bool,
+    pub codes: Vec<u8>,
+    /// Subset of `codes` reported as severe, by default the hail variants.
+    pub severe_codes: Vec<u8>,
+}
+
+impl Default for ThunderstormRule {
+    fn default() -> Self {
+        Self { enabled: true, codes: vec![95, 96, 99], severe_codes: vec![96, 99] }
+    }
+}
+
+/// Threshold rules evaluated against the daily forecast, loaded from the
+/// `[alerts]` section of the config file.
+///
+/// ```toml
+/// [alerts.heat]
+/// moderate = 32.0
+/// severe = 38.0
+///
+/// [alerts.frost]
+/// enabled = false
+/// ```
+///
+/// Every rule and every field is optional; anything left out keeps the
+/// defaults below.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertRules {
+    /// Daily maximum temperature, °C.
+    pub heat: ThresholdRule,
+    /// Daily minimum temperature, °C.
+    pub frost: ThresholdRule,
+    /// Daily maximum wind gusts, km/h.
+    pub wind: ThresholdRule,
+    /// Daily precipitation sum, mm.
+    pub precipitation: ThresholdRule,
+    pub thunderstorm: ThunderstormRule,
+}
+
+impl Default for AlertRules {
+    fn default() -> Self {
+        Self {
+            heat: ThresholdRule::new(30.0, 35.0),
+            frost: ThresholdRule::new(0.0, -10.0),
+            wind: ThresholdRule::new(60.0, 90.0),
+            precipitation: ThresholdRule::new(20.0, 50.0),
+            thunderstorm: ThunderstormRule::default(),
+        }
+    }
+}
+
+/// The `[alerts]` rule tables as written, before merging with the defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AlertRulesOverride {
+    heat: ThresholdOverride,
+    frost: ThresholdOverride,
+    wind: ThresholdOverride,
+    precipitation: ThresholdOverride,
+    thunderstorm: ThunderstormRule,
+}
+
+impl<'de> Deserialize<'de> for AlertRules {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let overrides = AlertRulesOverride::deserialize(deserializer)?;
+        let defaults = Self::default();
+        Ok(Self {
+            heat: overrides.heat.apply(defaults.heat),
+            frost: overrides.frost.apply(defaults.frost),
+            wind: overrides.wind.apply(defaults.wind),
+            precipitation: overrides.precipitation.apply(defaults.precipitation),
+            thunderstorm: overrides.thunderstorm,
+        })
+    }
+}
+
+impl AlertRules {
+    /// Evaluates every rule day by day. Consecutive days that trip the same
+    /// rule collapse into one alert carrying the worst severity and peak value.
+    pub fn evaluate(&self, forecast: &Forecast, units: &Units) -> Vec<Alert> {
+        let Some(daily) = &forecast.daily else {
+            return Vec::new();
+        };
+
+        let threshold_rules = [
+            (AlertKind::Heat, &self.heat, &daily.temperature_2m_max, "temperature_2m_max", true),
+            (AlertKind::Frost, &self.frost, &daily.temperature_2m_min, "temperature_2m_min", false),
+            (AlertKind::Wind, &self.wind, &daily.wind_gusts_10m_max, "wind_gusts_10m_max", true),
+            (AlertKind::Precipitation, &self.precipitation, &daily.precipitation_sum, "precipitation_sum", true),
+        ];
+
+        let mut alerts = Vec::new();
+        for (kind, rule, series, variable, above) in threshold_rules {
+            let convert = |value: f64| match kind {
+                AlertKind::Heat | AlertKind::Frost => units.temperature.from_celsius(value),
+                AlertKind::Wind => units.wind_speed.from_kmh(value),
+                _ => units.precipitation.from_mm(value),
+            };
+            let days = (0..daily.time.len()).map(|index| {
+                value_at(series, index).and_then(|value| {
+                    rule.severity(value, above, convert).map(|severity| (severity, value))
+                })
+            });
+            for (start, end, severity, peak) in spans(days, above) {
+                let unit = forecast.daily_unit(variable);
+                alerts.push(Alert {
+                    severity,
+                    kind,
+                    start: daily.time[start].clone(),
+                    end: daily.time[end].clone(),
+                    message: threshold_message(kind, &format_value(Some(peak), unit)),
+                    source: FORECAST_SOURCE.to_string(),
+                });
+            }
+        }
+
+        if self.thunderstorm.enabled {
+            alerts.extend(self.thunderstorms(daily));
+        }
+
+        alerts
+    }
+
+    fn thunderstorms(&self, daily: &DailyForecast) -> Vec<Alert> {
+        let rule = &self.thunderstorm;
+        // Codes are ordered by intensity, so the highest one is the peak.
+        let days = (0..daily.time.len()).map(|index| {
+            daily.weather_code
+                .get(index)
+                .copied()
+                .flatten()
+                .filter(|code| rule.codes.contains(code))
+                .map(|code| {
+                    let severity = if rule.severe_codes.contains(&code) { Severity::Severe } else { Severity::Moderate };
+                    (severity, f64::from(code))
+                })
+        });
+        spans(days, true)
+            .into_iter()
+            .map(|(start, end, severity, peak)| Alert {
+                severity,
+                kind: AlertKind::Thunderstorm,
+                start: daily.time[start].clone(),
+                end: daily.time[end].clone(),
+                message: format!("Forecast: {}", describe_weather_code(peak as u8)),
+                source: FORECAST_SOURCE.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Groups consecutive triggered days into `(start, end, worst severity, peak)`.
+fn spans(days: impl Iterator<Item = Option<(Severity, f64)>>, higher_is_worse: bool) -> Vec<(usize, usize, Severity, f64)> {
+    let mut spans: Vec<(usize, usize, Severity, f64)> = Vec::new();
+    for (index, day) in days.enumerate() {
+        let Some((severity, value)) = day else {
+            continue;
+        };
+        match spans.last_mut() {
+            Some(span) if span.1 + 1 == index => {
+                span.1 = index;
+                span.2 = span.2.max(severity);
+                if (higher_is_worse && value > span.3) || (!higher_is_worse && value < span.3) {
+                    span.3 = value;
+                }
+            }
+            _ => spans.push((index, index, severity, value)),
+        }
+    }
+    spans
+}
+
+fn threshold_message(kind: AlertKind, peak: &str) -> String {
+    match kind {
+        AlertKind::Heat => format!("Highs up to {}", peak),
+        AlertKind::Frost => format!("Lows down to {}", peak),
+        AlertKind::Wind => format!("Gusts up to {}", peak),
+        AlertKind::Precipitation => format!("Up to {} of precipitation in a day", peak),
+        AlertKind::Thunderstorm | AlertKind::Official => kind.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn forecast(daily: serde_json::Value, units: &str) -> Forecast {
+        let (temperature, wind, precipitation) = match units {
+            "imperial" => ("°F", "mp/h", "inch"),
+            _ => ("°C", "km/h", "mm"),
+        };
+        serde_json::from_value(serde_json::json!({
+            "latitude": 40.0,
+            "longitude": -75.0,
+            "timezone": null,
+            "elevation": null,
+            "current_weather": null,
+            "hourly": null,
+            "daily_units": {
+                "temperature_2m_max": temperature,
+                "temperature_2m_min": temperature,
+                "wind_gusts_10m_max": wind,
+                "precipitation_sum": precipitation
+            },
+            "daily": daily
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn partial_rule_tables_keep_the_defaults() {
+        let config: Config = toml::from_str(
+            "[alerts]\nforecast_days = 5\n\n[alerts.heat]\nenabled = false\n\n[alerts.wind]\nsevere = 120.0\n\n[alerts.thunderstorm]\nsevere_codes = [99]\n",
+        )
+        .unwrap();
+        let rules = &config.alerts.rules;
+        let defaults = AlertRules::default();
+
+        assert_eq!(config.alerts.forecast_days, 5);
+        assert_eq!(rules.heat, ThresholdRule { enabled: false, ..defaults.heat });
+        assert_eq!(rules.wind, ThresholdRule { severe: 120.0, ..defaults.wind });
+        assert_eq!(rules.frost, defaults.frost);
+        assert_eq!(rules.precipitation, defaults.precipitation);
+        assert_eq!(rules.thunderstorm.codes, [95, 96, 99]);
+        assert_eq!(rules.thunderstorm.severe_codes, [99]);
+    }
+
+    #[test]
+    fn empty_config_uses_default_rules() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert_eq!(config.alerts.rules, AlertRules::default());
+    }
+
+    #[test]
+    fn rejects_misspelled_threshold_fields() {
+        let error = toml::from_str::<Config>("[alerts.heat]\nsevre = 40.0\n").unwrap_err();
+
+        assert!(error.to_string().contains("sevre"), "{}", error);
+    }
+
+    #[test]
+    fn converts_thresholds_and_merges_consecutive_days() {
+        // Defaults are 30/35 °C (86/95 °F) for heat and 0/-10 °C (32/14 °F) for frost.
+        let forecast = forecast(
+            serde_json::json!({
+                "time": ["2025-07-01", "2025-07-02", "2025-07-03", "2025-07-04", "2025-07-05"],
+                "temperature_2m_max": [88.0, 96.0, 90.0, 70.0, 87.0],
+                "temperature_2m_min": [40.0, 31.0, 30.0, 40.0, 40.0]
+            }),
+            "imperial",
+        );
+
+        let alerts = AlertRules::default().evaluate(&forecast, &Units::imperial());
+
+        let heat: Vec<&Alert> = alerts.iter().filter(|alert| alert.kind == AlertKind::Heat).collect();
+        assert_eq!(heat.len(), 2);
+        assert_eq!((heat[0].start.as_str(), heat[0].end.as_str()), ("2025-07-01", "2025-07-03"));
+        assert_eq!(heat[0].severity, Severity::Severe);
+        assert_eq!(heat[0].message, "Highs up to 96°F");
+        assert_eq!((heat[1].start.as_str(), heat[1].severity), ("2025-07-05", Severity::Moderate));
+
+        let frost: Vec<&Alert> = alerts.iter().filter(|alert| alert.kind == AlertKind::Frost).collect();
+        assert_eq!(frost.len(), 1);
+        assert_eq!((frost[0].start.as_str(), frost[0].end.as_str()), ("2025-07-02", "2025-07-03"));
+        assert_eq!(frost[0].severity, Severity::Moderate);
+        assert_eq!(frost[0].message, "Lows down to 30°F");
+    }
+
+    #[test]
+    fn metric_forecasts_are_compared_unconverted() {
+        let forecast = forecast(
+            serde_json::json!({
+                "time": ["2025-07-01", "2025-07-02"],
+                "temperature_2m_max": [88.0, 29.9],
+                "wind_gusts_10m_max": [59.0, 95.0],
+                "precipitation_sum": [20.0, null]
+            }),
+            "metric",
+        );
+
+        let alerts = AlertRules::default().evaluate(&forecast, &Units::metric());
+
+        let summary: Vec<(AlertKind, Severity, &str)> =
+            alerts.iter().map(|alert| (alert.kind, alert.severity, alert.start.as_str())).collect();
+        assert_eq!(
+            summary,
+            [
+                (AlertKind::Heat, Severity::Severe, "2025-07-01"),
+                (AlertKind::Wind, Severity::Severe, "2025-07-02"),
+                (AlertKind::Precipitation, Severity::Moderate, "2025-07-01"),
+            ]
+        );
+        assert!(alerts.iter().all(|alert| alert.source == FORECAST_SOURCE));
+    }
+
+    #[test]
+    fn hail_codes_raise_severe_thunderstorms() {
+        let forecast = forecast(
+            serde_json::json!({
+                "time": ["2025-07-01", "2025-07-02", "2025-07-03", "2025-07-04"],
+                "weather_code": [95, 96, 3, 95]
+            }),
+            "metric",
+        );
+
+        let alerts = AlertRules::default().evaluate(&forecast, &Units::metric());
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!((alerts[0].start.as_str(), alerts[0].end.as_str()), ("2025-07-01", "2025-07-02"));
+        assert_eq!(alerts[0].severity, Severity::Severe);
+        assert_eq!(alerts[0].message, "Forecast: thunderstorm with slight hail");
+        assert_eq!((alerts[1].start.as_str(), alerts[1].severity), ("2025-07-04", Severity::Moderate));
+        assert_eq!(alerts[1].message, "Forecast: thunderstorm");
+    }
+}
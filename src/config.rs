@@ -0,0 +1,28 @@
+use anyhow::{Result, Context};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::{alerts::AlertsConfig, news::NewsConfig, notify::SinkConfig, units::Units, watch::WatchConfig};
+
+/// Settings loaded from a TOML file via `--config`. Every section is optional;
+/// command-line flags take precedence over values found here.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub units: Units,
+    pub alerts: AlertsConfig,
+    pub news: NewsConfig,
+    pub watch: WatchConfig,
+    /// Notification sinks by name, referenced from `[[watch.locations]]`.
+    pub sinks: BTreeMap<String, SinkConfig>,
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::{Result, Context};
+
+/// Reads a feed document from an HTTP(S) URL, a `file://` URL or a plain
+/// path. Local files let feeds be exercised against fixtures.
+pub(crate) async fn fetch_document(url: &str, accept: &str) -> Result<String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return std::fs::read_to_string(path).with_context(|| format!("Failed to read feed file {}", path));
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return std::fs::read_to_string(url).with_context(|| format!("Failed to read feed file {}", url));
+    }
+
+    let client = reqwest::Client::new();
+    client
+        .get(url)
+        .header("User-Agent", "multi-tool-agent/1.0")
+        .header("Accept", accept)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("{} returned an error", url))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read {}", url))
+}
@@ -31,6 +31,8 @@ impl GeocodingService {
                 latitude: geo_location.latitude,
                 longitude: geo_location.longitude,
                 country: geo_location.country,
+                country_code: geo_location.country_code,
+                admin1: geo_location.admin1,
             }),
             None => Err(anyhow::anyhow!("Location '{}' not found", location)),
         }
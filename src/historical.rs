@@ -0,0 +1,376 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::{
+    LocationInfo, HISTORICAL_WEATHER_API,
+    llm::Tool,
+    units::Units,
+    weather::{OpenMeteoError, describe_weather_code, format::{format_value, render_table, Column}},
+};
+
+/// Earliest date covered by the Open-Meteo archive (ERA5).
+const ARCHIVE_START: &str = "1940-01-01";
+/// Longest range one call may cover, to keep tool output readable.
+const MAX_RANGE_DAYS: i64 = 366;
+/// Ranges up to this long get a per-day table in addition to the summary.
+const MAX_TABLE_DAYS: usize = 31;
+
+/// Open-Meteo `/v1/archive` response with daily aggregates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricalWeatherData {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: Option<String>,
+    pub daily: HistoricalDaily,
+    #[serde(default)]
+    pub daily_units: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoricalDaily {
+    pub time: Vec<String>,
+    #[serde(default)]
+    pub weather_code: Vec<Option<u8>>,
+    #[serde(default)]
+    pub temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    pub temperature_2m_mean: Vec<Option<f64>>,
+    #[serde(default)]
+    pub precipitation_sum: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wind_speed_10m_max: Vec<Option<f64>>,
+}
+
+impl HistoricalWeatherData {
+    pub fn daily_unit(&self, variable: &str) -> &str {
+        self.daily_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Observed daily weather for past dates from the Open-Meteo archive.
+pub struct HistoricalWeather {
+    location_info: LocationInfo,
+    units: Units,
+    base_url: String,
+}
+
+impl HistoricalWeather {
+    pub fn new(location_info: LocationInfo) -> Self {
+        Self {
+            location_info,
+            units: Units::default(),
+            base_url: HISTORICAL_WEATHER_API.to_string(),
+        }
+    }
+
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Reads past weather from another `/v1/archive` URL instead of the
+    /// public Open-Meteo archive.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub async fn get_history(&self, start: NaiveDate, end: NaiveDate) -> Result<HistoricalWeatherData> {
+        validate_range(start, end, Local::now().date_naive())?;
+
+        let url = format!(
+            "{base}?latitude={lat}&longitude={lon}&start_date={start}&end_date={end}&daily=weather_code,temperature_2m_max,temperature_2m_min,temperature_2m_mean,precipitation_sum,wind_speed_10m_max&timezone=auto{units}",
+            base = self.base_url,
+            lat = self.location_info.latitude,
+            lon = self.location_info.longitude,
+            start = start,
+            end = end,
+            units = self.units.query_params()
+        );
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("User-Agent", "multi-tool-agent/1.0")
+            .send()
+            .await
+            .context("Failed to fetch historical weather data")?;
+
+        if !response.status().is_success() {
+            let error: OpenMeteoError = response
+                .json()
+                .await
+                .context("Failed to read historical weather error response")?;
+            anyhow::bail!("Open-Meteo rejected the archive request: {}", error.reason);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse historical weather response")
+    }
+
+    pub async fn get_history_text(&self, start: NaiveDate, end: NaiveDate) -> Result<String> {
+        let data = self.get_history(start, end).await?;
+        Ok(render_history(&self.location_info, &data))
+    }
+}
+
+fn validate_range(start: NaiveDate, end: NaiveDate, today: NaiveDate) -> Result<()> {
+    let archive_start = NaiveDate::parse_from_str(ARCHIVE_START, "%Y-%m-%d").expect("valid archive start date");
+
+    if start > end {
+        anyhow::bail!("start_date {} is after end_date {}", start, end);
+    }
+    if start < archive_start {
+        anyhow::bail!("The archive only goes back to {}", ARCHIVE_START);
+    }
+    if end >= today {
+        anyhow::bail!("end_date must be in the past; use the weather forecast tool for today and later");
+    }
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        anyhow::bail!("Date range is limited to {} days per request", MAX_RANGE_DAYS);
+    }
+
+    Ok(())
+}
+
+/// Summary of the period (averages, extremes, totals) followed by a per-day
+/// table for ranges up to a month.
+pub fn render_history(location_info: &LocationInfo, data: &HistoricalWeatherData) -> String {
+    let daily = &data.daily;
+    let (first, last) = match (daily.time.first(), daily.time.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return format!("No historical weather data for {}", location_info.name),
+    };
+
+    let mut output = format!(
+        "Observed weather for {} from {} to {} ({} days)\n",
+        location_info.name, first, last, daily.time.len()
+    );
+
+    let temperature_unit = data.daily_unit("temperature_2m_max");
+    let mean = |series: &[Option<f64>]| {
+        let values: Vec<f64> = series.iter().flatten().copied().collect();
+        (!values.is_empty()).then(|| (values.iter().sum::<f64>() / values.len() as f64 * 10.0).round() / 10.0)
+    };
+    let extreme = |series: &[Option<f64>], pick_max: bool| {
+        series
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| value.map(|value| (index, value)))
+            .reduce(|best, candidate| {
+                let better = if pick_max { candidate.1 > best.1 } else { candidate.1 < best.1 };
+                if better { candidate } else { best }
+            })
+    };
+
+    output.push_str(&format!(
+        "- Average high {}, average low {}, mean {}\n",
+        format_value(mean(&daily.temperature_2m_max), temperature_unit),
+        format_value(mean(&daily.temperature_2m_min), temperature_unit),
+        format_value(mean(&daily.temperature_2m_mean), temperature_unit)
+    ));
+    if let Some((index, value)) = extreme(&daily.temperature_2m_max, true) {
+        output.push_str(&format!("- Hottest: {} on {}\n", format_value(Some(value), temperature_unit), daily.time[index]));
+    }
+    if let Some((index, value)) = extreme(&daily.temperature_2m_min, false) {
+        output.push_str(&format!("- Coldest: {} on {}\n", format_value(Some(value), temperature_unit), daily.time[index]));
+    }
+
+    let precipitation: Vec<f64> = daily.precipitation_sum.iter().flatten().copied().collect();
+    if !precipitation.is_empty() {
+        let unit = data.daily_unit("precipitation_sum");
+        // A wet day is the usual 1 mm threshold, converted when reporting inches.
+        let wet_threshold = if unit.starts_with("inch") { 0.04 } else { 1.0 };
+        let total = (precipitation.iter().sum::<f64>() * 100.0).round() / 100.0;
+        let wet_days = precipitation.iter().filter(|&&amount| amount >= wet_threshold).count();
+        output.push_str(&format!(
+            "- Total precipitation {} over {} wet days\n",
+            format_value(Some(total), unit),
+            wet_days
+        ));
+    }
+    if let Some((index, value)) = extreme(&daily.wind_speed_10m_max, true) {
+        output.push_str(&format!(
+            "- Windiest: {} on {}\n",
+            format_value(Some(value), data.daily_unit("wind_speed_10m_max")),
+            daily.time[index]
+        ));
+    }
+
+    if daily.time.len() <= MAX_TABLE_DAYS {
+        let rows: Vec<usize> = (0..daily.time.len()).collect();
+        let numeric = |header, series: &[Option<f64>], variable| {
+            Column::numeric(header, series, &rows, data.daily_unit(variable))
+        };
+        let columns = [
+            Column::text("Date", daily.time.clone()),
+            Column::text(
+                "Conditions",
+                rows.iter()
+                    .map(|&index| {
+                        daily.weather_code
+                            .get(index)
+                            .copied()
+                            .flatten()
+                            .map_or("n/a", describe_weather_code)
+                            .to_string()
+                    })
+                    .collect(),
+            ),
+            numeric("Min", &daily.temperature_2m_min, "temperature_2m_min"),
+            numeric("Max", &daily.temperature_2m_max, "temperature_2m_max"),
+            numeric("Precip", &daily.precipitation_sum, "precipitation_sum"),
+            numeric("Wind max", &daily.wind_speed_10m_max, "wind_speed_10m_max"),
+        ];
+        output.push('\n');
+        output.push_str(&render_table(&columns));
+    }
+
+    output
+}
+
+#[async_trait]
+impl Tool for HistoricalWeather {
+    fn name(&self) -> &str {
+        "get_historical_weather"
+    }
+
+    fn description(&self) -> &str {
+        "Get observed daily weather (temperatures, precipitation, wind) for past dates, up to a year per call"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "start_date": {
+                    "type": "string",
+                    "description": "First day, YYYY-MM-DD"
+                },
+                "end_date": {
+                    "type": "string",
+                    "description": "Last day, YYYY-MM-DD, before today"
+                }
+            },
+            "required": ["start_date", "end_date"]
+        })
+    }
+
+    async fn call(&self, args: serde_json::Value) -> Result<String> {
+        let date = |field: &str| -> Result<NaiveDate> {
+            let value = args[field].as_str().with_context(|| format!("Missing {}", field))?;
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .with_context(|| format!("{} must be YYYY-MM-DD, got '{}'", field, value))
+        };
+        self.get_history_text(date("start_date")?, date("end_date")?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{StubServer, location};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn data(precipitation_unit: &str) -> HistoricalWeatherData {
+        serde_json::from_value(serde_json::json!({
+            "latitude": 35.7,
+            "longitude": 139.7,
+            "timezone": "Asia/Tokyo",
+            "daily_units": {
+                "temperature_2m_max": "°F",
+                "temperature_2m_min": "°F",
+                "temperature_2m_mean": "°F",
+                "precipitation_sum": precipitation_unit,
+                "wind_speed_10m_max": "mp/h"
+            },
+            "daily": {
+                "time": ["2024-07-01", "2024-07-02", "2024-07-03"],
+                "weather_code": [0, 63, null],
+                "temperature_2m_max": [88.0, 95.5, null],
+                "temperature_2m_min": [70.0, 72.0, 66.5],
+                "temperature_2m_mean": [79.0, 83.0, 75.0],
+                "precipitation_sum": [0.0, 0.5, 0.03],
+                "wind_speed_10m_max": [10.0, 22.4, 8.0]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        let today = date("2025-06-15");
+        let error = |start, end| validate_range(date(start), date(end), today).unwrap_err().to_string();
+
+        assert!(error("2024-02-10", "2024-02-01").contains("after end_date"));
+        assert!(error("1939-12-31", "1940-01-10").contains("1940-01-01"));
+        assert!(error("2025-06-01", "2025-06-15").contains("in the past"));
+        assert!(error("2025-06-01", "2025-06-20").contains("in the past"));
+        assert!(error("2023-01-01", "2024-01-02").contains("366 days"));
+    }
+
+    #[test]
+    fn accepts_past_ranges_up_to_366_days() {
+        let today = date("2025-06-15");
+
+        assert!(validate_range(date("1940-01-01"), date("1940-01-01"), today).is_ok());
+        assert!(validate_range(date("2024-01-01"), date("2024-12-31"), today).is_ok());
+        assert!(validate_range(date("2025-06-01"), date("2025-06-14"), today).is_ok());
+    }
+
+    #[test]
+    fn summarizes_extremes_and_wet_days_in_inches() {
+        let output = render_history(&location("Tokyo", 35.7, 139.7), &data("inch"));
+
+        assert!(output.starts_with("Observed weather for Tokyo from 2024-07-01 to 2024-07-03 (3 days)"), "{}", output);
+        assert!(output.contains("- Average high 91.8°F, average low 69.5°F, mean 79°F"), "{}", output);
+        assert!(output.contains("- Hottest: 95.5°F on 2024-07-02"), "{}", output);
+        assert!(output.contains("- Coldest: 66.5°F on 2024-07-03"), "{}", output);
+        // 0.03 inch is under the 1 mm threshold, 0.5 inch is over it.
+        assert!(output.contains("- Total precipitation 0.53 inch over 1 wet days"), "{}", output);
+        assert!(output.contains("- Windiest: 22.4 mp/h on 2024-07-02"), "{}", output);
+        assert!(output.contains("moderate rain"), "{}", output);
+    }
+
+    #[test]
+    fn counts_wet_days_from_one_millimetre() {
+        let output = render_history(&location("Tokyo", 35.7, 139.7), &data("mm"));
+
+        assert!(output.contains("- Total precipitation 0.53 mm over 0 wet days"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn requests_the_archive_with_units() {
+        let body = serde_json::to_string(&data("inch")).unwrap();
+        let server = StubServer::start(vec![(200, body.as_str())]).await;
+        let history = HistoricalWeather::new(location("Tokyo", 35.7, 139.7))
+            .with_units(Units::imperial())
+            .with_base_url(format!("{}/v1/archive", server.url));
+
+        let output = history.get_history_text(date("2024-07-01"), date("2024-07-03")).await.unwrap();
+
+        assert!(output.contains("Hottest: 95.5°F"));
+        let path = &server.requests()[0].path;
+        assert!(path.starts_with("/v1/archive?latitude=35.7&longitude=139.7&start_date=2024-07-01&end_date=2024-07-03"), "{}", path);
+        assert!(path.contains("&temperature_unit=fahrenheit"), "{}", path);
+    }
+
+    #[tokio::test]
+    async fn reports_open_meteo_errors() {
+        let server = StubServer::start(vec![(400, r#"{"error": true, "reason": "Parameter 'daily' is invalid"}"#)]).await;
+        let history = HistoricalWeather::new(location("Tokyo", 35.7, 139.7)).with_base_url(&server.url);
+
+        let error = history.get_history(date("2024-07-01"), date("2024-07-03")).await.unwrap_err();
+
+        assert!(error.to_string().contains("Parameter 'daily' is invalid"));
+    }
+}
@@ -1,8 +1,12 @@
 use serde::Deserialize;
 
 pub const WEATHER_API: &str = "https://api.open-meteo.com/v1/forecast";
-pub const NEWS_API: &str = "https://newsapi.org/v2/top-headlines";
+pub const HISTORICAL_WEATHER_API: &str = "https://archive-api.open-meteo.com/v1/archive";
+pub const AIR_QUALITY_API: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+pub const MARINE_API: &str = "https://marine-api.open-meteo.com/v1/marine";
+pub const NEWS_API: &str = "https://newsapi.org/v2";
 pub const NEWS_API_KEY: &str = "your_newsapi_key_here"; // Replace with actual API key
+pub const GNEWS_API: &str = "https://gnews.io/api/v4";
 pub const GEOCODING_API: &str = "https://geocoding-api.open-meteo.com/v1/search";
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +20,8 @@ pub struct GeoLocation {
     pub longitude: f64,
     pub name: String,
     pub country: Option<String>,
+    /// ISO 3166-1 alpha-2 code, e.g. "JP".
+    pub country_code: Option<String>,
     pub admin1: Option<String>,
 }
 
@@ -25,11 +31,26 @@ pub struct LocationInfo {
     pub latitude: f64,
     pub longitude: f64,
     pub country: Option<String>,
+    /// ISO 3166-1 alpha-2 code, e.g. "JP".
+    pub country_code: Option<String>,
+    /// First-level administrative region (state, prefecture, ...).
+    pub admin1: Option<String>,
 }
 
+pub mod config;
+mod feeds;
+pub mod units;
 pub mod geocoding;
 pub mod weather;
+pub mod historical;
+pub mod air_quality;
+pub mod marine;
 pub mod news;
 pub mod alerts;
+pub mod watch;
+pub mod notify;
 pub mod location;
-pub mod llm; 
\ No newline at end of file
+pub mod llm;
+
+#[cfg(test)]
+mod test_support;
\ No newline at end of file
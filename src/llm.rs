@@ -1,186 +1,496 @@
 use anyhow::{Result, Context};
-use crate::{LocationInfo, weather::Weather, news::News, alerts::Alerts, location::LocationDetails};
-use std::env;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use crate::{LocationInfo, config::Config, weather::Weather, historical::HistoricalWeather, air_quality::AirQuality, marine::Marine, news::News, alerts::Alerts, location::LocationDetails};
+
+pub mod claude;
+pub mod conversation;
+pub mod gemini;
+pub mod ollama;
+pub mod openai;
+mod sse;
+
+pub use claude::ClaudeProvider;
+pub use conversation::Conversation;
+pub use gemini::GeminiProvider;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiProvider;
+
+/// A capability the agent can invoke on behalf of the model.
+///
+/// Implement this to expose your own data source to `LLMProcessor`; the
+/// name, description and parameter schema are what the model gets to see.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Identifier the model uses to request this tool, e.g. `get_weather`.
+    fn name(&self) -> &str;
+
+    /// One-line description of what the tool returns.
+    fn description(&self) -> &str;
+
+    /// JSON schema for the tool arguments. Defaults to an empty object.
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object", "properties": {} })
+    }
+
+    async fn call(&self, args: serde_json::Value) -> Result<String>;
+}
+
+/// Ordered collection of tools available to the agent.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the built-in weather, history, air quality, marine, news,
+    /// alerts and location tools.
+    pub fn with_defaults(location_info: &LocationInfo, config: &Config) -> Self {
+        let mut registry = Self::new();
+        registry.register(Weather::new(location_info.clone()).with_units(config.units));
+        registry.register(HistoricalWeather::new(location_info.clone()).with_units(config.units));
+        registry.register(AirQuality::new(location_info.clone()));
+        registry.register(Marine::new(location_info.clone()).with_units(config.units));
+        registry.register(News::new(location_info.clone()).with_config(config.news.clone()));
+        registry.register(
+            Alerts::new(location_info.clone())
+                .with_units(config.units)
+                .with_config(config.alerts.clone()),
+        );
+        registry.register(LocationDetails::new(location_info.clone()));
+        registry
+    }
+
+    /// Adds a tool, replacing any previously registered tool with the same name.
+    pub fn register(&mut self, tool: impl Tool + 'static) {
+        self.tools.retain(|existing| existing.name() != tool.name());
+        self.tools.push(Box::new(tool));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.tools.iter().find(|tool| tool.name() == name).map(|tool| tool.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Tool> {
+        self.tools.iter().map(|tool| tool.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Provider-neutral declarations of every registered tool.
+    pub fn specs(&self) -> Vec<ToolSpec> {
+        self.iter()
+            .map(|tool| ToolSpec {
+                name: tool.name().to_string(),
+                description: tool.description().to_string(),
+                parameters: tool.parameters(),
+            })
+            .collect()
+    }
+
+    pub async fn call(&self, name: &str, args: serde_json::Value) -> Result<String> {
+        let tool = self.get(name)
+            .with_context(|| format!("Unknown tool '{}'", name))?;
+        tool.call(args).await
+    }
+}
+
+/// Tool declaration as sent to a model.
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+/// A tool invocation requested by the model.
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    /// Provider-assigned id used to pair the call with its result.
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// Output of a tool call, fed back to the model on the next turn.
+#[derive(Debug, Clone)]
+pub struct ToolResult {
+    pub call_id: String,
+    pub name: String,
+    pub content: String,
+    pub is_error: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    User(String),
+    Assistant { text: String, tool_calls: Vec<ToolCall> },
+    ToolResult(ToolResult),
+}
+
+pub struct ChatRequest<'a> {
+    pub system: &'a str,
+    pub messages: &'a [Message],
+    pub tools: &'a [ToolSpec],
+}
+
+/// A model turn: free text, tool calls, or both.
+#[derive(Debug, Clone, Default)]
+pub struct ChatResponse {
+    pub text: String,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Incremental output of a streaming model turn.
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    TextDelta(String),
+    /// A complete tool call; providers buffer partial arguments until then.
+    ToolCall(ToolCall),
+}
+
+/// A chat backend that understands tool calling.
+///
+/// Implementations translate the neutral `ChatRequest` into their wire format
+/// and normalize the reply; the agent loop lives in `LLMProcessor`.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Display name, e.g. `Gemini`.
+    fn name(&self) -> &str;
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<ChatResponse>;
+
+    /// Streams the turn as it is generated. Providers without native
+    /// streaming inherit this, which replays `chat` as a single burst.
+    async fn chat_stream(&self, request: &ChatRequest<'_>) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        let response = self.chat(request).await?;
+
+        let mut events = Vec::new();
+        if !response.text.is_empty() {
+            events.push(Ok(StreamEvent::TextDelta(response.text)));
+        }
+        events.extend(response.tool_calls.into_iter().map(|call| Ok(StreamEvent::ToolCall(call))));
+
+        Ok(stream::iter(events).boxed())
+    }
+}
+
+/// Names accepted by `provider_from_name`.
+pub const PROVIDERS: &[&str] = &["gemini", "claude", "openai", "ollama"];
+
+/// Overrides applied on top of a provider's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderOptions {
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (OpenAI-compatible only).
+    pub api_key_env: Option<String>,
+}
+
+/// Builds a provider by name, reading credentials from the environment.
+pub fn provider_from_name(name: &str, options: &ProviderOptions) -> Result<Box<dyn LlmProvider>> {
+    match name.to_lowercase().as_str() {
+        "gemini" => {
+            let mut provider = GeminiProvider::from_env()?;
+            if let Some(model) = &options.model {
+                provider = provider.with_model(model);
+            }
+            if let Some(base_url) = &options.base_url {
+                provider = provider.with_base_url(base_url);
+            }
+            Ok(Box::new(provider))
+        }
+        "claude" | "anthropic" => {
+            let mut provider = ClaudeProvider::from_env()?;
+            if let Some(model) = &options.model {
+                provider = provider.with_model(model);
+            }
+            if let Some(base_url) = &options.base_url {
+                provider = provider.with_base_url(base_url);
+            }
+            Ok(Box::new(provider))
+        }
+        "openai" => {
+            let api_key_env = options.api_key_env.as_deref().unwrap_or(openai::DEFAULT_API_KEY_ENV);
+            let mut provider = OpenAiProvider::from_env(options.base_url.as_deref(), api_key_env)?;
+            if let Some(model) = &options.model {
+                provider = provider.with_model(model);
+            }
+            Ok(Box::new(provider))
+        }
+        "ollama" => {
+            let mut provider = OllamaProvider::from_env();
+            if let Some(model) = &options.model {
+                provider = provider.with_model(model);
+            }
+            if let Some(base_url) = &options.base_url {
+                provider = provider.with_base_url(base_url);
+            }
+            Ok(Box::new(provider))
+        }
+        other => Err(anyhow::anyhow!(
+            "Unknown LLM provider '{}'. Available providers: {}",
+            other,
+            PROVIDERS.join(", ")
+        )),
+    }
+}
+
+/// Progress of an agent turn as surfaced by `LLMProcessor::process_turn_stream`.
+#[derive(Debug, Clone)]
+pub enum AgentEvent {
+    TextDelta(String),
+    ToolCall(ToolCall),
+    ToolResult(ToolResult),
+}
+
+/// Upper bound on model round trips per query unless overridden.
+pub const DEFAULT_MAX_STEPS: usize = 5;
 
 pub struct LLMProcessor {
-    weather: Weather,
-    news: News,
-    alerts: Alerts,
-    location_details: LocationDetails,
+    provider: Box<dyn LlmProvider>,
+    tools: ToolRegistry,
     location_info: LocationInfo,
+    max_steps: usize,
 }
 
 impl LLMProcessor {
-    pub fn new(location_info: LocationInfo) -> Self {
-        Self {
-            weather: Weather::new(location_info.clone()),
-            news: News::new(location_info.clone()),
-            alerts: Alerts::new(location_info.clone()),
-            location_details: LocationDetails::new(location_info.clone()),
-            location_info,
-        }
+    pub fn new(location_info: LocationInfo, provider: Box<dyn LlmProvider>) -> Self {
+        let tools = ToolRegistry::with_defaults(&location_info, &Config::default());
+        Self::with_tools(location_info, provider, tools)
+    }
+
+    pub fn with_tools(location_info: LocationInfo, provider: Box<dyn LlmProvider>, tools: ToolRegistry) -> Self {
+        Self { provider, tools, location_info, max_steps: DEFAULT_MAX_STEPS }
+    }
+
+    /// Limits how many model calls a single query may take, counting the
+    /// final answer. A model that keeps requesting tools past this is stopped.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    pub fn provider(&self) -> &dyn LlmProvider {
+        self.provider.as_ref()
+    }
+
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
+    pub fn register_tool(&mut self, tool: impl Tool + 'static) {
+        self.tools.register(tool);
     }
 
-    pub async fn process_query(&self, query: &str, use_gemini: bool, use_claude: bool) -> Result<String> {
-        if use_gemini {
-            self.process_with_gemini(query).await
-        } else if use_claude {
-            self.process_with_claude(query).await
-        } else {
-            Err(anyhow::anyhow!("No LLM API selected. Use --gemini or --claude flag."))
+    /// Answers a one-off query with no prior context.
+    pub async fn process_query(&self, query: &str) -> Result<String> {
+        self.process_turn(&mut Conversation::new(), query).await
+    }
+
+    /// Answers `query` in the context of `conversation`, appending the user
+    /// turn, any tool traffic and the final answer to it. A failed turn is
+    /// removed again so the history stays well-formed.
+    pub async fn process_turn(&self, conversation: &mut Conversation, query: &str) -> Result<String> {
+        let mut events = std::pin::pin!(self.process_turn_stream(conversation, query));
+        let mut answer = String::new();
+
+        while let Some(event) = events.next().await {
+            match event? {
+                AgentEvent::TextDelta(delta) => answer.push_str(&delta),
+                // Only the text after the last tool round is the answer.
+                AgentEvent::ToolResult(_) => answer.clear(),
+                AgentEvent::ToolCall(_) => {}
+            }
         }
+
+        Ok(answer)
     }
 
-    async fn process_with_gemini(&self, query: &str) -> Result<String> {
-        let api_key = env::var("GEMINI_API_KEY")
-            .context("GEMINI_API_KEY environment variable not set")?;
-        
-        let client = reqwest::Client::new();
-        
-        let system_prompt = format!(
-            "You are a location information agent for {}. You have access to these tools:\n\
-            - get_weather(): Get current weather and 3-day forecast\n\
-            - get_news(): Get latest news headlines\n\
-            - get_alerts(): Get weather alerts and warnings\n\
-            - get_location_info(): Get location details\n\n\
-            Based on the user's query, determine which tool to call and provide a helpful response. \
-            Be specific about what action you're taking and respond in a conversational manner.",
-            self.location_info.name
-        );
+    /// Streaming form of `process_turn`: yields text as the model produces
+    /// it, plus each tool call and its result between model steps.
+    pub fn process_turn_stream<'a>(
+        &'a self,
+        conversation: &'a mut Conversation,
+        query: &str,
+    ) -> impl Stream<Item = Result<AgentEvent>> + 'a {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let query = query.to_string();
+
+        let driver = async move {
+            conversation.push(Message::User(query));
+            let turn_start = conversation.len() - 1;
+
+            if let Err(e) = self.run_agent_loop(conversation, &sender).await {
+                conversation.truncate(turn_start);
+                let _ = sender.unbounded_send(Err(e));
+            }
+        };
+
+        // The loop runs as part of polling the returned stream; it ends once
+        // the loop finishes and the sender is dropped.
+        let driver = stream::once(driver).filter_map(|()| async { None });
+        stream::select(receiver, driver)
+    }
 
-        let full_prompt = format!("{}\n\nUser query: {}\n\nResponse:", system_prompt, query);
-
-        let url = format!("https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash-latest:generateContent?key={}", api_key);
-        
-        println!("DEBUG: Calling Gemini API with URL: {}", url);
-        println!("DEBUG: Prompt: {}", &full_prompt[..full_prompt.len().min(200)]);
-
-        let response = client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&serde_json::json!({
-                "contents": [{
-                    "parts": [{
-                        "text": full_prompt
-                    }]
-                }],
-                "generationConfig": {
-                    "temperature": 0.7,
-                    "topK": 40,
-                    "topP": 0.95,
-                    "maxOutputTokens": 500
+    async fn run_agent_loop(
+        &self,
+        conversation: &mut Conversation,
+        sender: &futures::channel::mpsc::UnboundedSender<Result<AgentEvent>>,
+    ) -> Result<()> {
+        let system_prompt = self.system_prompt();
+        let tools = self.tools.specs();
+        let emit = |event| {
+            let _ = sender.unbounded_send(Ok(event));
+        };
+
+        for step in 1..=self.max_steps {
+            let mut events = self.provider
+                .chat_stream(&ChatRequest { system: &system_prompt, messages: conversation.messages(), tools: &tools })
+                .await?;
+
+            let mut text = String::new();
+            let mut tool_calls = Vec::new();
+            while let Some(event) = events.next().await {
+                match event? {
+                    StreamEvent::TextDelta(delta) => {
+                        text.push_str(&delta);
+                        emit(AgentEvent::TextDelta(delta));
+                    }
+                    StreamEvent::ToolCall(call) => {
+                        tool_calls.push(call.clone());
+                        emit(AgentEvent::ToolCall(call));
+                    }
                 }
-            }))
-            .send()
-            .await
-            .context("Failed to call Gemini API")?;
-            
-        println!("DEBUG: Gemini response status: {}", response.status());
-
-        let response_data: serde_json::Value = response.json().await
-            .context("Failed to parse Gemini response")?;
-
-        let content = response_data["candidates"][0]["content"]["parts"][0]["text"]
-            .as_str()
-            .context("Invalid Gemini response format")?;
-
-        self.execute_tool_based_on_response(content, query).await
-    }
-
-    async fn process_with_claude(&self, query: &str) -> Result<String> {
-        let api_key = env::var("ANTHROPIC_API_KEY")
-            .context("ANTHROPIC_API_KEY environment variable not set")?;
-        
-        let client = reqwest::Client::new();
-        
-        let system_prompt = format!(
-            "You are a location information agent for {}. You have access to these tools:\n\
-            - get_weather(): Get current weather and 3-day forecast\n\
-            - get_news(): Get latest news headlines\n\
-            - get_alerts(): Get weather alerts and warnings\n\
-            - get_location_info(): Get location details\n\n\
-            Based on the user's query, determine which tool to call and provide a helpful response. \
-            Be specific about what action you're taking and respond in a conversational manner.",
-            self.location_info.name
-        );
+            }
 
-        let response = client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("Content-Type", "application/json")
-            .header("anthropic-version", "2023-06-01")
-            .json(&serde_json::json!({
-                "model": "claude-3-haiku-20240307",
-                "max_tokens": 500,
-                "system": system_prompt,
-                "messages": [{
-                    "role": "user",
-                    "content": query
-                }]
-            }))
-            .send()
-            .await
-            .context("Failed to call Claude API")?;
-
-        let response_data: serde_json::Value = response.json().await
-            .context("Failed to parse Claude response")?;
-
-        let content = response_data["content"][0]["text"]
-            .as_str()
-            .context("Invalid Claude response format")?;
-
-        self.execute_tool_based_on_response(content, query).await
-    }
-
-    async fn execute_tool_based_on_response(&self, llm_response: &str, original_query: &str) -> Result<String> {
-        let response_lower = llm_response.to_lowercase();
-        let query_lower = original_query.to_lowercase();
-        
-        println!("DEBUG: execute_tool_based_on_response called");
-        println!("DEBUG: Original query: '{}'", original_query);
-        println!("DEBUG: LLM response: '{}'", llm_response);
-        
-        // More robust tool selection based primarily on the original query
-        let tool_result = if query_lower.contains("alert") || query_lower.contains("warning") || 
-                              query_lower.contains("emergency") || query_lower.contains("danger") {
-            println!("DEBUG: Calling alerts tool");
-            Some(self.alerts.get_alerts().await?)
-        } else if query_lower.contains("news") || query_lower.contains("headlines") || 
-                   query_lower.contains("latest") || query_lower.contains("breaking") {
-            println!("DEBUG: Calling news tool");
-            Some(self.news.get_news().await?)
-        } else if query_lower.contains("weather") || query_lower.contains("temperature") || 
-                   query_lower.contains("forecast") || query_lower.contains("climate") {
-            println!("DEBUG: Calling weather tool");
-            Some(self.weather.get_weather().await?)
-        } else if query_lower.contains("location") || query_lower.contains("coordinates") || 
-                   query_lower.contains("where") || query_lower.contains("info") {
-            println!("DEBUG: Calling location tool");
-            Some(self.location_details.get_location_info().await?)
-        } else {
-            println!("DEBUG: No direct match, checking LLM response for tool hints");
-            // Fallback: check if LLM response mentions any tools
-            if response_lower.contains("weather") || response_lower.contains("get_weather") {
-                println!("DEBUG: LLM mentioned weather, calling weather tool");
-                Some(self.weather.get_weather().await?)
-            } else if response_lower.contains("news") || response_lower.contains("get_news") {
-                println!("DEBUG: LLM mentioned news, calling news tool");
-                Some(self.news.get_news().await?)
-            } else if response_lower.contains("alert") || response_lower.contains("get_alerts") {
-                println!("DEBUG: LLM mentioned alerts, calling alerts tool");
-                Some(self.alerts.get_alerts().await?)
-            } else if response_lower.contains("location") || response_lower.contains("get_location") {
-                println!("DEBUG: LLM mentioned location, calling location tool");
-                Some(self.location_details.get_location_info().await?)
-            } else {
-                println!("DEBUG: No tool called - returning LLM response only");
-                None
+            if tool_calls.is_empty() {
+                // Claude sometimes ends a turn after a tool result with no
+                // content at all; both Claude and Gemini reject such a message
+                // in the history, which would break every later turn.
+                if !text.is_empty() {
+                    conversation.push(Message::Assistant { text, tool_calls });
+                }
+                return Ok(());
             }
+            conversation.push(Message::Assistant { text, tool_calls: tool_calls.clone() });
+
+            for call in tool_calls {
+                tracing::debug!("Step {}: {} requested tool {} with {}", step, self.provider.name(), call.name, call.arguments);
+                let result = self.run_tool(call).await;
+                emit(AgentEvent::ToolResult(result.clone()));
+                conversation.push(Message::ToolResult(result));
+            }
+        }
+
+        Err(anyhow::anyhow!("{} did not produce an answer within {} steps", self.provider.name(), self.max_steps))
+    }
+
+    /// Executes a tool call, reporting failures to the model rather than
+    /// aborting the query so it can recover or explain.
+    async fn run_tool(&self, call: ToolCall) -> ToolResult {
+        let (content, is_error) = match self.tools.call(&call.name, call.arguments).await {
+            Ok(output) => (output, false),
+            Err(e) => (format!("Error: {:#}", e), true),
         };
+        ToolResult { call_id: call.id, name: call.name, content, is_error }
+    }
 
-        match tool_result {
-            Some(data) => Ok(format!("{}\n\n{}", llm_response, data)),
-            None => Ok(llm_response.to_string()),
+    fn system_prompt(&self) -> String {
+        let tool_list: String = self.tools
+            .iter()
+            .map(|tool| format!("- {}(): {}\n", tool.name(), tool.description()))
+            .collect();
+
+        format!(
+            "You are a location information agent for {}. Today's date is {}. \
+            You have access to these tools:\n\
+            {}\n\
+            Based on the user's query, call the tools you need (several in turn if necessary) \
+            and answer from their results. Summarize the data in plain language instead of \
+            repeating raw JSON, and respond in a conversational manner.",
+            self.location_info.name,
+            chrono::Local::now().format("%A, %Y-%m-%d"),
+            tool_list
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Replays canned turns in order.
+    struct ScriptedProvider {
+        turns: Mutex<Vec<ChatResponse>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "Scripted"
         }
+
+        async fn chat(&self, _request: &ChatRequest<'_>) -> Result<ChatResponse> {
+            Ok(self.turns.lock().unwrap().remove(0))
+        }
+    }
+
+    struct Echo;
+
+    #[async_trait]
+    impl Tool for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echoes its arguments"
+        }
+
+        async fn call(&self, args: serde_json::Value) -> Result<String> {
+            Ok(args.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_final_turn_is_not_kept() {
+        let call = ToolCall { id: "call_0".to_string(), name: "echo".to_string(), arguments: serde_json::json!({"x": 1}) };
+        let provider = ScriptedProvider {
+            turns: Mutex::new(vec![
+                ChatResponse { text: String::new(), tool_calls: vec![call] },
+                ChatResponse::default(),
+            ]),
+        };
+        let mut tools = ToolRegistry::new();
+        tools.register(Echo);
+        let location = crate::LocationInfo {
+            name: "Tokyo".to_string(),
+            latitude: 35.68,
+            longitude: 139.69,
+            country: None,
+            country_code: None,
+            admin1: None,
+        };
+        let processor = LLMProcessor::with_tools(location, Box::new(provider), tools);
+        let mut conversation = Conversation::new();
+
+        let answer = processor.process_turn(&mut conversation, "Echo x").await.unwrap();
+
+        assert_eq!(answer, "");
+        let messages = conversation.messages();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(&messages[2], Message::ToolResult(result) if result.content == r#"{"x":1}"# && !result.is_error));
     }
-} 
\ No newline at end of file
+}
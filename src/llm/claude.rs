@@ -0,0 +1,330 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use super::{sse, ChatRequest, ChatResponse, LlmProvider, Message, StreamEvent, ToolCall};
+use std::collections::HashMap;
+use std::env;
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+const DEFAULT_MODEL: &str = "claude-3-haiku-20240307";
+
+pub struct ClaudeProvider {
+    api_key: String,
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl ClaudeProvider {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let api_key = env::var("ANTHROPIC_API_KEY").context(
+            "ANTHROPIC_API_KEY environment variable not set\n\
+            Set it with: export ANTHROPIC_API_KEY='your-key-here'",
+        )?;
+        Ok(Self::new(api_key))
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>, stream: bool) -> Result<reqwest::Response> {
+        let tools: Vec<serde_json::Value> = request.tools
+            .iter()
+            .map(|tool| serde_json::json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.parameters
+            }))
+            .collect();
+
+        self.client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .header("anthropic-version", "2023-06-01")
+            .json(&serde_json::json!({
+                "model": self.model,
+                "max_tokens": 1024,
+                "system": request.system,
+                "tools": tools,
+                "messages": Self::messages(request.messages),
+                "stream": stream
+            }))
+            .send()
+            .await
+            .context("Failed to call Claude API")
+    }
+
+    fn messages(messages: &[Message]) -> Vec<serde_json::Value> {
+        let mut wire: Vec<serde_json::Value> = Vec::new();
+
+        for message in messages {
+            match message {
+                Message::User(text) => wire.push(serde_json::json!({
+                    "role": "user",
+                    "content": text
+                })),
+                Message::Assistant { text, tool_calls } => {
+                    let mut content = Vec::new();
+                    if !text.is_empty() {
+                        content.push(serde_json::json!({ "type": "text", "text": text }));
+                    }
+                    for call in tool_calls {
+                        content.push(serde_json::json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.name,
+                            "input": call.arguments
+                        }));
+                    }
+                    // The API rejects empty content; such a turn adds nothing anyway.
+                    if !content.is_empty() {
+                        wire.push(serde_json::json!({ "role": "assistant", "content": content }));
+                    }
+                }
+                Message::ToolResult(result) => {
+                    let block = serde_json::json!({
+                        "type": "tool_result",
+                        "tool_use_id": result.call_id,
+                        "content": result.content,
+                        "is_error": result.is_error
+                    });
+
+                    // Claude expects every tool_result for a turn in one user message.
+                    match wire.last_mut() {
+                        Some(last) if last["content"][0]["type"] == "tool_result" => {
+                            if let Some(content) = last["content"].as_array_mut() {
+                                content.push(block);
+                            }
+                        }
+                        _ => wire.push(serde_json::json!({ "role": "user", "content": [block] })),
+                    }
+                }
+            }
+        }
+
+        wire
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ClaudeProvider {
+    fn name(&self) -> &str {
+        "Claude"
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<ChatResponse> {
+        let response = self.send(request, false).await?;
+
+        let response_data: serde_json::Value = response.json().await
+            .context("Failed to parse Claude response")?;
+
+        if let Some(error) = response_data.get("error") {
+            anyhow::bail!("Claude API error: {}", error["message"].as_str().unwrap_or("unknown error"));
+        }
+
+        let blocks = response_data["content"]
+            .as_array()
+            .context("Invalid Claude response format")?;
+
+        let mut chat_response = ChatResponse::default();
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("text") => {
+                    if !chat_response.text.is_empty() {
+                        chat_response.text.push('\n');
+                    }
+                    chat_response.text.push_str(block["text"].as_str().unwrap_or_default());
+                }
+                Some("tool_use") => chat_response.tool_calls.push(ToolCall {
+                    id: block["id"].as_str().context("Claude tool_use block without an id")?.to_string(),
+                    name: block["name"].as_str().context("Claude tool_use block without a name")?.to_string(),
+                    arguments: block["input"].clone(),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(chat_response)
+    }
+
+    async fn chat_stream(&self, request: &ChatRequest<'_>) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        let response = self.send(request, true).await?;
+
+        if !response.status().is_success() {
+            let response_data: serde_json::Value = response.json().await
+                .context("Failed to parse Claude response")?;
+            anyhow::bail!("Claude API error: {}", response_data["error"]["message"].as_str().unwrap_or("unknown error"));
+        }
+
+        let mut decoder = StreamDecoder::default();
+        Ok(sse::events(response)
+            .map(move |event| event.and_then(|event| decoder.decode(event)))
+            .map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed())
+    }
+}
+
+/// A `tool_use` block whose JSON input is still arriving.
+struct PendingToolUse {
+    id: String,
+    name: String,
+    input_json: String,
+}
+
+/// Turns Claude's `content_block_*` SSE events into `StreamEvent`s.
+#[derive(Default)]
+struct StreamDecoder {
+    pending: HashMap<u64, PendingToolUse>,
+}
+
+impl StreamDecoder {
+    fn decode(&mut self, event: sse::SseEvent) -> Result<Vec<StreamEvent>> {
+        let data: serde_json::Value = serde_json::from_str(&event.data)
+            .context("Failed to parse Claude stream event")?;
+        let index = data["index"].as_u64().unwrap_or_default();
+
+        match data["type"].as_str() {
+            Some("content_block_start") if data["content_block"]["type"] == "tool_use" => {
+                let block = &data["content_block"];
+                self.pending.insert(index, PendingToolUse {
+                    id: block["id"].as_str().context("Claude tool_use block without an id")?.to_string(),
+                    name: block["name"].as_str().context("Claude tool_use block without a name")?.to_string(),
+                    input_json: String::new(),
+                });
+            }
+            Some("content_block_delta") => {
+                let delta = &data["delta"];
+                match delta["type"].as_str() {
+                    Some("text_delta") => {
+                        let text = delta["text"].as_str().unwrap_or_default();
+                        return Ok(vec![StreamEvent::TextDelta(text.to_string())]);
+                    }
+                    Some("input_json_delta") => {
+                        if let Some(pending) = self.pending.get_mut(&index) {
+                            pending.input_json.push_str(delta["partial_json"].as_str().unwrap_or_default());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Some("content_block_stop") => {
+                if let Some(pending) = self.pending.remove(&index) {
+                    let arguments = if pending.input_json.trim().is_empty() {
+                        serde_json::json!({})
+                    } else {
+                        serde_json::from_str(&pending.input_json)
+                            .with_context(|| format!("Invalid input for tool call {}", pending.name))?
+                    };
+                    return Ok(vec![StreamEvent::ToolCall(ToolCall { id: pending.id, name: pending.name, arguments })]);
+                }
+            }
+            Some("error") => {
+                anyhow::bail!("Claude API error: {}", data["error"]["message"].as_str().unwrap_or("unknown error"));
+            }
+            _ => {}
+        }
+
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{ToolCall, ToolResult};
+
+    #[test]
+    fn skips_empty_assistant_turns() {
+        let call = ToolCall { id: "toolu_1".to_string(), name: "get_weather".to_string(), arguments: serde_json::json!({}) };
+        let messages = [
+            Message::User("Weather?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: vec![call] },
+            Message::ToolResult(ToolResult {
+                call_id: "toolu_1".to_string(),
+                name: "get_weather".to_string(),
+                content: "Sunny".to_string(),
+                is_error: false,
+            }),
+            Message::Assistant { text: String::new(), tool_calls: Vec::new() },
+            Message::User("And tomorrow?".to_string()),
+        ];
+
+        let wire = ClaudeProvider::messages(&messages);
+
+        let roles: Vec<&str> = wire.iter().map(|message| message["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, ["user", "assistant", "user", "user"]);
+        assert_eq!(wire[1]["content"][0]["type"], "tool_use");
+        assert_eq!(wire[2]["content"][0]["tool_use_id"], "toolu_1");
+        assert!(wire.iter().all(|message| message["content"] != serde_json::json!([])));
+    }
+
+    fn event(data: serde_json::Value) -> sse::SseEvent {
+        sse::SseEvent { event: data["type"].as_str().map(str::to_string), data: data.to_string() }
+    }
+
+    #[test]
+    fn buffers_tool_input_until_the_block_stops() {
+        let mut decoder = StreamDecoder::default();
+        let events = [
+            serde_json::json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            serde_json::json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Checking"}}),
+            serde_json::json!({"type": "content_block_stop", "index": 0}),
+            serde_json::json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {}}}),
+            serde_json::json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"da"}}),
+            serde_json::json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "ys\": 3}"}}),
+        ];
+
+        let decoded: Vec<StreamEvent> = events.into_iter().flat_map(|data| decoder.decode(event(data)).unwrap()).collect();
+        assert_eq!(decoded.len(), 1);
+        assert!(matches!(&decoded[0], StreamEvent::TextDelta(text) if text == "Checking"));
+
+        let stop = decoder.decode(event(serde_json::json!({"type": "content_block_stop", "index": 1}))).unwrap();
+        let [StreamEvent::ToolCall(call)] = stop.as_slice() else {
+            panic!("expected one tool call, got {:?}", stop);
+        };
+        assert_eq!(call.id, "toolu_1");
+        assert_eq!(call.name, "get_weather");
+        assert_eq!(call.arguments, serde_json::json!({"days": 3}));
+    }
+
+    #[test]
+    fn tool_use_without_input_gets_empty_arguments() {
+        let mut decoder = StreamDecoder::default();
+        decoder
+            .decode(event(serde_json::json!({"type": "content_block_start", "index": 0, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "get_location_details"}})))
+            .unwrap();
+
+        let stop = decoder.decode(event(serde_json::json!({"type": "content_block_stop", "index": 0}))).unwrap();
+
+        assert!(matches!(&stop[..], [StreamEvent::ToolCall(call)] if call.arguments == serde_json::json!({})));
+    }
+
+    #[test]
+    fn reports_stream_errors() {
+        let mut decoder = StreamDecoder::default();
+
+        let error = decoder
+            .decode(event(serde_json::json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}})))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("Overloaded"));
+    }
+}
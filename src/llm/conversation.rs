@@ -0,0 +1,138 @@
+use super::Message;
+
+/// Number of user turns kept when no window is configured.
+pub const DEFAULT_HISTORY_WINDOW: usize = 10;
+
+/// Running transcript of a chat session.
+///
+/// History is bounded by user turns rather than raw messages so a tool call is
+/// never separated from its result; older turns are dropped as new ones start.
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    messages: Vec<Message>,
+    history_window: usize,
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self::with_history_window(DEFAULT_HISTORY_WINDOW)
+    }
+
+    /// Keeps at most `turns` user turns (including the current one), minimum 1.
+    pub fn with_history_window(turns: usize) -> Self {
+        Self { messages: Vec::new(), history_window: turns.max(1) }
+    }
+
+    pub fn history_window(&self) -> usize {
+        self.history_window
+    }
+
+    pub fn push(&mut self, message: Message) {
+        let starts_turn = matches!(message, Message::User(_));
+        self.messages.push(message);
+        if starts_turn {
+            self.trim();
+        }
+    }
+
+    /// Messages within the history window, oldest first.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Drops messages added after `len`, used to discard a failed turn.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.messages.truncate(len);
+    }
+
+    fn trim(&mut self) {
+        let turn_starts: Vec<usize> = self.messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| matches!(message, Message::User(_)))
+            .map(|(index, _)| index)
+            .collect();
+
+        if turn_starts.len() > self.history_window {
+            let first_kept = turn_starts[turn_starts.len() - self.history_window];
+            self.messages.drain(..first_kept);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{ToolCall, ToolResult};
+
+    fn tool_turn(conversation: &mut Conversation, question: &str) {
+        let call = ToolCall { id: format!("{}-call", question), name: "get_weather".to_string(), arguments: serde_json::json!({}) };
+        conversation.push(Message::User(question.to_string()));
+        conversation.push(Message::Assistant { text: String::new(), tool_calls: vec![call] });
+        conversation.push(Message::ToolResult(ToolResult {
+            call_id: format!("{}-call", question),
+            name: "get_weather".to_string(),
+            content: "Sunny".to_string(),
+            is_error: false,
+        }));
+        conversation.push(Message::Assistant { text: "It is sunny.".to_string(), tool_calls: Vec::new() });
+    }
+
+    #[test]
+    fn keeps_the_last_turns_whole() {
+        let mut conversation = Conversation::with_history_window(2);
+        for question in ["one", "two", "three"] {
+            tool_turn(&mut conversation, question);
+        }
+
+        assert_eq!(conversation.len(), 8);
+        assert!(matches!(&conversation.messages()[0], Message::User(text) if text == "two"));
+        assert!(matches!(&conversation.messages()[4], Message::User(text) if text == "three"));
+    }
+
+    #[test]
+    fn never_separates_a_tool_call_from_its_result() {
+        let mut conversation = Conversation::with_history_window(1);
+        tool_turn(&mut conversation, "one");
+        conversation.push(Message::User("two".to_string()));
+
+        // Trimming only happens as a user turn starts, so the window always
+        // begins with a user message and every call keeps its result.
+        assert_eq!(conversation.len(), 1);
+        tool_turn(&mut conversation, "three");
+        let messages = conversation.messages();
+        assert!(matches!(&messages[0], Message::User(text) if text == "three"));
+        let Message::Assistant { tool_calls, .. } = &messages[1] else {
+            panic!("expected the tool call, got {:?}", messages[1]);
+        };
+        assert!(matches!(&messages[2], Message::ToolResult(result) if result.call_id == tool_calls[0].id));
+    }
+
+    #[test]
+    fn window_is_at_least_one_turn() {
+        let mut conversation = Conversation::with_history_window(0);
+        conversation.push(Message::User("one".to_string()));
+        conversation.push(Message::User("two".to_string()));
+
+        assert_eq!(conversation.history_window(), 1);
+        assert_eq!(conversation.len(), 1);
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use super::{sse, ChatRequest, ChatResponse, LlmProvider, Message, StreamEvent, ToolCall};
+use std::env;
+
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const DEFAULT_MODEL: &str = "gemini-1.5-flash-latest";
+
+pub struct GeminiProvider {
+    api_key: String,
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl GeminiProvider {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let api_key = env::var("GEMINI_API_KEY").context(
+            "GEMINI_API_KEY environment variable not set\n\
+            Set it with: export GEMINI_API_KEY='your-key-here'\n\
+            Get your key from: https://makersuite.google.com/app/apikey",
+        )?;
+        Ok(Self::new(api_key))
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    fn contents(messages: &[Message]) -> Vec<serde_json::Value> {
+        let mut contents: Vec<serde_json::Value> = Vec::new();
+
+        for message in messages {
+            match message {
+                Message::User(text) => contents.push(serde_json::json!({
+                    "role": "user",
+                    "parts": [{ "text": text }]
+                })),
+                Message::Assistant { text, tool_calls } => {
+                    let mut parts = Vec::new();
+                    if !text.is_empty() {
+                        parts.push(serde_json::json!({ "text": text }));
+                    }
+                    for call in tool_calls {
+                        parts.push(serde_json::json!({
+                            "functionCall": { "name": call.name, "args": call.arguments }
+                        }));
+                    }
+                    // The API rejects empty parts; such a turn adds nothing anyway.
+                    if !parts.is_empty() {
+                        contents.push(serde_json::json!({ "role": "model", "parts": parts }));
+                    }
+                }
+                Message::ToolResult(result) => {
+                    let response = if result.is_error {
+                        serde_json::json!({ "error": result.content })
+                    } else {
+                        serde_json::json!({ "content": result.content })
+                    };
+                    let part = serde_json::json!({
+                        "functionResponse": { "name": result.name, "response": response }
+                    });
+
+                    // All responses to one model turn go back in a single content.
+                    match contents.last_mut() {
+                        Some(last) if last["parts"][0].get("functionResponse").is_some() => {
+                            if let Some(parts) = last["parts"].as_array_mut() {
+                                parts.push(part);
+                            }
+                        }
+                        _ => contents.push(serde_json::json!({ "role": "user", "parts": [part] })),
+                    }
+                }
+            }
+        }
+
+        contents
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>, method: &str) -> Result<reqwest::Response> {
+        let separator = if method.contains('?') { '&' } else { '?' };
+        let url = format!("{}/models/{}:{}{}key={}", self.base_url, self.model, method, separator, self.api_key);
+
+        let mut body = serde_json::json!({
+            "systemInstruction": {
+                "parts": [{ "text": request.system }]
+            },
+            "contents": Self::contents(request.messages),
+            "generationConfig": {
+                "temperature": 0.7,
+                "topK": 40,
+                "topP": 0.95,
+                "maxOutputTokens": 1024
+            }
+        });
+        if !request.tools.is_empty() {
+            body["tools"] = serde_json::json!([{
+                "functionDeclarations": Self::function_declarations(request)
+            }]);
+        }
+
+        self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to call Gemini API")
+    }
+
+    fn function_declarations(request: &ChatRequest<'_>) -> Vec<serde_json::Value> {
+        request.tools
+            .iter()
+            .map(|tool| {
+                let mut declaration = serde_json::json!({
+                    "name": tool.name,
+                    "description": tool.description
+                });
+                // Gemini rejects OBJECT schemas without properties, so parameterless
+                // tools are declared without a schema.
+                if tool.parameters["properties"].as_object().is_some_and(|properties| !properties.is_empty()) {
+                    declaration["parameters"] = tool.parameters.clone();
+                }
+                declaration
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    fn name(&self) -> &str {
+        "Gemini"
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<ChatResponse> {
+        let response = self.send(request, "generateContent").await?;
+
+        tracing::debug!("Gemini response status: {}", response.status());
+
+        let response_data: serde_json::Value = response.json().await
+            .context("Failed to parse Gemini response")?;
+
+        if let Some(error) = response_data.get("error") {
+            anyhow::bail!("Gemini API error: {}", error["message"].as_str().unwrap_or("unknown error"));
+        }
+
+        let candidate = &response_data["candidates"][0];
+        if candidate["content"]["parts"].as_array().is_none() {
+            anyhow::bail!(
+                "Gemini returned no content (finishReason: {})",
+                candidate["finishReason"].as_str().unwrap_or("unknown")
+            );
+        }
+
+        let mut chat_response = ChatResponse::default();
+        for event in candidate_events(candidate, &mut 0)? {
+            match event {
+                StreamEvent::TextDelta(text) => chat_response.text.push_str(&text),
+                StreamEvent::ToolCall(call) => chat_response.tool_calls.push(call),
+            }
+        }
+
+        Ok(chat_response)
+    }
+
+    async fn chat_stream(&self, request: &ChatRequest<'_>) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        let response = self.send(request, "streamGenerateContent?alt=sse").await?;
+
+        if !response.status().is_success() {
+            let response_data: serde_json::Value = response.json().await
+                .context("Failed to parse Gemini response")?;
+            anyhow::bail!("Gemini API error: {}", response_data["error"]["message"].as_str().unwrap_or("unknown error"));
+        }
+
+        let mut tool_calls_seen = 0;
+        Ok(sse::events(response)
+            .map(move |event| {
+                let chunk: serde_json::Value = serde_json::from_str(&event?.data)
+                    .context("Failed to parse Gemini stream chunk")?;
+                if let Some(error) = chunk.get("error") {
+                    anyhow::bail!("Gemini API error: {}", error["message"].as_str().unwrap_or("unknown error"));
+                }
+                candidate_events(&chunk["candidates"][0], &mut tool_calls_seen)
+            })
+            .map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed())
+    }
+}
+
+/// Text and function calls in a (possibly partial) candidate. `tool_calls_seen`
+/// numbers calls across stream chunks, since Gemini does not assign call ids.
+fn candidate_events(candidate: &serde_json::Value, tool_calls_seen: &mut usize) -> Result<Vec<StreamEvent>> {
+    let mut events = Vec::new();
+
+    for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+        if let Some(text) = part["text"].as_str() {
+            events.push(StreamEvent::TextDelta(text.to_string()));
+        }
+        if let Some(call) = part.get("functionCall") {
+            let name = call["name"].as_str().context("Gemini functionCall without a name")?;
+            let arguments = match &call["args"] {
+                serde_json::Value::Null => serde_json::json!({}),
+                args => args.clone(),
+            };
+            events.push(StreamEvent::ToolCall(ToolCall {
+                id: format!("{}-{}", name, tool_calls_seen),
+                name: name.to_string(),
+                arguments,
+            }));
+            *tool_calls_seen += 1;
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{ToolCall, ToolResult};
+
+    #[test]
+    fn skips_empty_model_turns() {
+        let call = ToolCall { id: "call_0".to_string(), name: "get_weather".to_string(), arguments: serde_json::json!({}) };
+        let messages = [
+            Message::User("Weather?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: vec![call] },
+            Message::ToolResult(ToolResult {
+                call_id: "call_0".to_string(),
+                name: "get_weather".to_string(),
+                content: "Sunny".to_string(),
+                is_error: false,
+            }),
+            Message::Assistant { text: String::new(), tool_calls: Vec::new() },
+            Message::User("And tomorrow?".to_string()),
+        ];
+
+        let contents = GeminiProvider::contents(&messages);
+
+        let roles: Vec<&str> = contents.iter().map(|content| content["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, ["user", "model", "user", "user"]);
+        assert_eq!(contents[1]["parts"][0]["functionCall"]["name"], "get_weather");
+        assert_eq!(contents[2]["parts"][0]["functionResponse"]["response"]["content"], "Sunny");
+        assert!(contents.iter().all(|content| content["parts"] != serde_json::json!([])));
+    }
+}
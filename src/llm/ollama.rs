@@ -0,0 +1,410 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use super::{ChatRequest, ChatResponse, LlmProvider, Message, ToolCall, ToolSpec};
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+pub const DEFAULT_MODEL: &str = "llama3.1";
+
+/// A local Ollama daemon via `/api/chat`.
+///
+/// Models with native tool support get `tools` definitions. For models that
+/// reject them the provider switches, once, to describing the tools in the
+/// system prompt and reading a JSON tool request back out of the reply.
+pub struct OllamaProvider {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+    native_tools: AtomicBool,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: model.into(),
+            client: reqwest::Client::new(),
+            native_tools: AtomicBool::new(true),
+        }
+    }
+
+    /// Reads `OLLAMA_HOST` and `OLLAMA_MODEL`, defaulting to a local daemon.
+    pub fn from_env() -> Self {
+        let base_url = env::var("OLLAMA_HOST")
+            .map(|host| if host.starts_with("http") { host } else { format!("http://{}", host) })
+            .unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        Self::new(base_url, model)
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    fn native_messages(request: &ChatRequest<'_>) -> Vec<serde_json::Value> {
+        let mut wire = vec![serde_json::json!({ "role": "system", "content": request.system })];
+
+        for message in request.messages {
+            wire.push(match message {
+                Message::User(text) => serde_json::json!({ "role": "user", "content": text }),
+                Message::Assistant { text, tool_calls } => {
+                    let calls: Vec<serde_json::Value> = tool_calls
+                        .iter()
+                        .map(|call| serde_json::json!({
+                            "function": { "name": call.name, "arguments": call.arguments }
+                        }))
+                        .collect();
+                    serde_json::json!({ "role": "assistant", "content": text, "tool_calls": calls })
+                }
+                Message::ToolResult(result) => serde_json::json!({
+                    "role": "tool",
+                    "content": result.content
+                }),
+            });
+        }
+
+        wire
+    }
+
+    /// Messages for models without tool support: tools are described in the
+    /// system prompt and tool traffic is replayed as plain text turns.
+    fn prompted_messages(request: &ChatRequest<'_>) -> Vec<serde_json::Value> {
+        let system = format!("{}\n\n{}", request.system, tool_prompt(request.tools));
+        let mut wire = vec![serde_json::json!({ "role": "system", "content": system })];
+
+        for message in request.messages {
+            wire.push(match message {
+                Message::User(text) => serde_json::json!({ "role": "user", "content": text }),
+                Message::Assistant { text, tool_calls } if tool_calls.is_empty() => {
+                    serde_json::json!({ "role": "assistant", "content": text })
+                }
+                // One request per line, so every replayed result has its call.
+                Message::Assistant { tool_calls, .. } => {
+                    let content: Vec<String> = tool_calls
+                        .iter()
+                        .map(|call| serde_json::json!({ "tool": call.name, "arguments": call.arguments }).to_string())
+                        .collect();
+                    serde_json::json!({ "role": "assistant", "content": content.join("\n") })
+                }
+                Message::ToolResult(result) => serde_json::json!({
+                    "role": "user",
+                    "content": format!("Result of {}:\n{}", result.name, result.content)
+                }),
+            });
+        }
+
+        wire
+    }
+
+    async fn send(&self, body: serde_json::Value) -> Result<(reqwest::StatusCode, serde_json::Value)> {
+        let response = self.client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Failed to call Ollama at {}. Is `ollama serve` running?", self.base_url))?;
+
+        let status = response.status();
+        let response_data: serde_json::Value = response.json().await
+            .context("Failed to parse Ollama response")?;
+
+        Ok((status, response_data))
+    }
+
+    async fn chat_native(&self, request: &ChatRequest<'_>) -> Result<Option<ChatResponse>> {
+        let tools: Vec<serde_json::Value> = request.tools
+            .iter()
+            .map(|tool| serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.parameters
+                }
+            }))
+            .collect();
+
+        let (status, response_data) = self.send(serde_json::json!({
+            "model": self.model,
+            "stream": false,
+            "messages": Self::native_messages(request),
+            "tools": tools
+        })).await?;
+
+        if let Some(error) = response_data["error"].as_str() {
+            if !status.is_success() && error.contains("does not support tools") {
+                return Ok(None);
+            }
+            anyhow::bail!("Ollama error: {}", error);
+        }
+
+        let message = &response_data["message"];
+        let mut chat_response = ChatResponse {
+            text: message["content"].as_str().unwrap_or_default().to_string(),
+            tool_calls: Vec::new(),
+        };
+
+        let first_id = calls_so_far(request);
+        for (index, call) in message["tool_calls"].as_array().into_iter().flatten().enumerate() {
+            let name = call["function"]["name"].as_str().context("Ollama tool call without a function name")?;
+            chat_response.tool_calls.push(ToolCall {
+                id: format!("call_{}", first_id + index),
+                name: name.to_string(),
+                arguments: match &call["function"]["arguments"] {
+                    serde_json::Value::Null => serde_json::json!({}),
+                    arguments => arguments.clone(),
+                },
+            });
+        }
+
+        Ok(Some(chat_response))
+    }
+
+    async fn chat_prompted(&self, request: &ChatRequest<'_>) -> Result<ChatResponse> {
+        let (_, response_data) = self.send(serde_json::json!({
+            "model": self.model,
+            "stream": false,
+            "messages": Self::prompted_messages(request)
+        })).await?;
+
+        if let Some(error) = response_data["error"].as_str() {
+            anyhow::bail!("Ollama error: {}", error);
+        }
+
+        let text = response_data["message"]["content"].as_str().unwrap_or_default();
+
+        Ok(match parse_tool_request(text, request.tools, format!("call_{}", calls_so_far(request))) {
+            Some(call) => ChatResponse { text: String::new(), tool_calls: vec![call] },
+            None => ChatResponse { text: text.to_string(), tool_calls: Vec::new() },
+        })
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<ChatResponse> {
+        if !request.tools.is_empty() && self.native_tools.load(Ordering::Relaxed) {
+            if let Some(response) = self.chat_native(request).await? {
+                return Ok(response);
+            }
+            tracing::debug!("Model {} does not support tools, falling back to prompt-based selection", self.model);
+            self.native_tools.store(false, Ordering::Relaxed);
+        }
+
+        self.chat_prompted(request).await
+    }
+}
+
+/// Ollama does not assign call ids; numbering calls after the ones already
+/// in the conversation keeps them unique across turns.
+fn calls_so_far(request: &ChatRequest<'_>) -> usize {
+    request
+        .messages
+        .iter()
+        .map(|message| match message {
+            Message::Assistant { tool_calls, .. } => tool_calls.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Instructions that let a model without native tool calling request a tool.
+fn tool_prompt(tools: &[ToolSpec]) -> String {
+    let tool_list: String = tools
+        .iter()
+        .map(|tool| format!("- {}: {} Arguments schema: {}\n", tool.name, tool.description, tool.parameters))
+        .collect();
+
+    format!(
+        "To use a tool, reply with only a JSON object of the form \
+        {{\"tool\": \"<name>\", \"arguments\": {{...}}}} and nothing else. \
+        You will then receive the tool result. Otherwise reply normally.\n\
+        Available tools:\n{}",
+        tool_list
+    )
+}
+
+/// Extracts a `{"tool": ..., "arguments": ...}` request naming a known tool.
+fn parse_tool_request(text: &str, tools: &[ToolSpec], id: String) -> Option<ToolCall> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(text.get(start..=end)?).ok()?;
+
+    let name = value["tool"].as_str()?;
+    if !tools.iter().any(|tool| tool.name == name) {
+        return None;
+    }
+
+    Some(ToolCall {
+        id,
+        name: name.to_string(),
+        arguments: match &value["arguments"] {
+            serde_json::Value::Null => serde_json::json!({}),
+            arguments => arguments.clone(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ToolResult;
+    use crate::test_support::StubServer;
+
+    fn weather_tool() -> ToolSpec {
+        ToolSpec {
+            name: "get_weather".to_string(),
+            description: "Weather".to_string(),
+            parameters: serde_json::json!({"type": "object", "properties": {}}),
+        }
+    }
+
+    fn call(id: &str, name: &str) -> ToolCall {
+        ToolCall { id: id.to_string(), name: name.to_string(), arguments: serde_json::json!({}) }
+    }
+
+    fn result(id: &str, name: &str, content: &str) -> Message {
+        Message::ToolResult(ToolResult {
+            call_id: id.to_string(),
+            name: name.to_string(),
+            content: content.to_string(),
+            is_error: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn parses_native_tool_calls() {
+        let server = StubServer::start(vec![(200, r#"{"message": {"role": "assistant", "content": "", "tool_calls": [
+            {"function": {"name": "get_weather", "arguments": {"days": 2}}},
+            {"function": {"name": "get_news"}}
+        ]}, "done": true}"#)])
+        .await;
+        let provider = OllamaProvider::new(&server.url, "llama3.1");
+        let messages = [
+            Message::User("Weather?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: vec![call("call_0", "get_weather")] },
+            result("call_0", "get_weather", "Sunny"),
+            Message::Assistant { text: "Sunny.".to_string(), tool_calls: Vec::new() },
+            Message::User("And tomorrow, with news?".to_string()),
+        ];
+        let tools = [weather_tool()];
+
+        let response = provider.chat(&ChatRequest { system: "Test", messages: &messages, tools: &tools }).await.unwrap();
+
+        assert_eq!(response.tool_calls.len(), 2);
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"days": 2}));
+        assert_eq!(response.tool_calls[1].id, "call_2");
+        assert_eq!(response.tool_calls[1].arguments, serde_json::json!({}));
+        let sent = &server.requests()[0];
+        assert_eq!(sent.path, "/api/chat");
+        let body = sent.json();
+        assert_eq!(body["model"], "llama3.1");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(body["messages"][3]["role"], "tool");
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_prompted_tools_once() {
+        let server = StubServer::start(vec![
+            (400, r#"{"error": "registry.ollama.ai/library/gemma:2b does not support tools"}"#),
+            (200, r#"{"message": {"role": "assistant", "content": "{\"tool\": \"get_weather\", \"arguments\": {\"days\": 1}}"}}"#),
+            (200, r#"{"message": {"role": "assistant", "content": "It will be sunny."}}"#),
+        ])
+        .await;
+        let provider = OllamaProvider::new(&server.url, "gemma:2b");
+        let tools = [weather_tool()];
+        let first = [Message::User("Weather?".to_string())];
+
+        let response = provider.chat(&ChatRequest { system: "Test", messages: &first, tools: &tools }).await.unwrap();
+
+        assert_eq!(response.text, "");
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "call_0");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"days": 1}));
+
+        let second = [
+            Message::User("Weather?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: response.tool_calls },
+            result("call_0", "get_weather", "Sunny"),
+        ];
+        let response = provider.chat(&ChatRequest { system: "Test", messages: &second, tools: &tools }).await.unwrap();
+
+        assert_eq!(response.text, "It will be sunny.");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].json()["tools"].is_array());
+        assert!(requests[1].json().get("tools").is_none());
+        assert!(requests[2].json().get("tools").is_none(), "native tools are not retried");
+        let system = requests[1].json()["messages"][0]["content"].as_str().unwrap().to_string();
+        assert!(system.contains("- get_weather: Weather"), "{}", system);
+    }
+
+    #[tokio::test]
+    async fn reports_other_errors() {
+        let server = StubServer::start(vec![(404, r#"{"error": "model \"nope\" not found, try pulling it first"}"#)]).await;
+        let provider = OllamaProvider::new(&server.url, "nope");
+        let messages = [Message::User("Hi".to_string())];
+        let tools = [weather_tool()];
+
+        let error = provider.chat(&ChatRequest { system: "Test", messages: &messages, tools: &tools }).await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Ollama error: model \"nope\" not found, try pulling it first");
+    }
+
+    #[test]
+    fn replays_every_call_of_a_turn_in_prompted_mode() {
+        let messages = [
+            Message::User("Weather and news?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: vec![call("call_0", "get_weather"), call("call_1", "get_news")] },
+            result("call_0", "get_weather", "Sunny"),
+            result("call_1", "get_news", "No news"),
+            Message::Assistant { text: "Sunny, no news.".to_string(), tool_calls: Vec::new() },
+        ];
+        let tools = [weather_tool()];
+
+        let wire = OllamaProvider::prompted_messages(&ChatRequest { system: "Test", messages: &messages, tools: &tools });
+
+        assert_eq!(wire.len(), 6);
+        assert_eq!(
+            wire[2],
+            serde_json::json!({
+                "role": "assistant",
+                "content": "{\"arguments\":{},\"tool\":\"get_weather\"}\n{\"arguments\":{},\"tool\":\"get_news\"}"
+            })
+        );
+        assert_eq!(wire[3], serde_json::json!({"role": "user", "content": "Result of get_weather:\nSunny"}));
+        assert_eq!(wire[4], serde_json::json!({"role": "user", "content": "Result of get_news:\nNo news"}));
+        assert_eq!(wire[5], serde_json::json!({"role": "assistant", "content": "Sunny, no news."}));
+    }
+
+    #[test]
+    fn parses_tool_requests_from_text() {
+        let tools = [weather_tool()];
+        let id = || "call_3".to_string();
+
+        let wrapped = parse_tool_request("Sure!\n```json\n{\"tool\": \"get_weather\", \"arguments\": {\"days\": 3}}\n```", &tools, id())
+            .unwrap();
+        let bare = parse_tool_request("{\"tool\": \"get_weather\"}", &tools, id()).unwrap();
+
+        assert_eq!(wrapped.id, "call_3");
+        assert_eq!(wrapped.name, "get_weather");
+        assert_eq!(wrapped.arguments, serde_json::json!({"days": 3}));
+        assert_eq!(bare.arguments, serde_json::json!({}));
+        assert!(parse_tool_request("{\"tool\": \"launch_rocket\"}", &tools, id()).is_none());
+        assert!(parse_tool_request("It is {sunny} today.", &tools, id()).is_none());
+        assert!(parse_tool_request("No tools needed.", &tools, id()).is_none());
+    }
+}
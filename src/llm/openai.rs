@@ -0,0 +1,271 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use super::{ChatRequest, ChatResponse, LlmProvider, Message, ToolCall};
+use std::env;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+pub const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// Any server speaking the OpenAI `/v1/chat/completions` protocol: OpenAI
+/// itself, vLLM, llama.cpp server, LM Studio and friends.
+pub struct OpenAiProvider {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OpenAiProvider {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            model: model.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Builds a provider against `base_url`, falling back to `OPENAI_BASE_URL`
+    /// and then the OpenAI endpoint. The model comes from `OPENAI_MODEL` and
+    /// the key from `api_key_env`.
+    ///
+    /// A missing key is only an error against the default OpenAI endpoint;
+    /// local servers usually run without authentication.
+    pub fn from_env(base_url: Option<&str>, api_key_env: &str) -> Result<Self> {
+        let base_url = base_url
+            .map(str::to_string)
+            .or_else(|| env::var("OPENAI_BASE_URL").ok())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        let mut provider = Self::new(base_url, model);
+
+        match env::var(api_key_env) {
+            Ok(api_key) => provider.api_key = Some(api_key),
+            Err(_) if provider.base_url == DEFAULT_BASE_URL => anyhow::bail!(
+                "{} environment variable not set\n\
+                Set it with: export {}='your-key-here', or point --base-url at a local server",
+                api_key_env, api_key_env
+            ),
+            Err(_) => {}
+        }
+
+        Ok(provider)
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    fn messages(request: &ChatRequest<'_>) -> Vec<serde_json::Value> {
+        let mut wire = vec![serde_json::json!({ "role": "system", "content": request.system })];
+
+        for message in request.messages {
+            wire.push(match message {
+                Message::User(text) => serde_json::json!({ "role": "user", "content": text }),
+                Message::Assistant { text, tool_calls } if tool_calls.is_empty() => {
+                    serde_json::json!({ "role": "assistant", "content": text })
+                }
+                Message::Assistant { text, tool_calls } => {
+                    let calls: Vec<serde_json::Value> = tool_calls
+                        .iter()
+                        .map(|call| serde_json::json!({
+                            "id": call.id,
+                            "type": "function",
+                            "function": {
+                                "name": call.name,
+                                "arguments": call.arguments.to_string()
+                            }
+                        }))
+                        .collect();
+                    let content = if text.is_empty() { serde_json::Value::Null } else { text.as_str().into() };
+                    serde_json::json!({ "role": "assistant", "content": content, "tool_calls": calls })
+                }
+                Message::ToolResult(result) => serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": result.call_id,
+                    "content": result.content
+                }),
+            });
+        }
+
+        wire
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "OpenAI-compatible"
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<ChatResponse> {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": 1024,
+            "messages": Self::messages(request)
+        });
+        if !request.tools.is_empty() {
+            let tools: Vec<serde_json::Value> = request.tools
+                .iter()
+                .map(|tool| serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters
+                    }
+                }))
+                .collect();
+            body["tools"] = tools.into();
+        }
+
+        let mut http_request = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            http_request = http_request.bearer_auth(api_key);
+        }
+
+        let response = http_request
+            .send()
+            .await
+            .with_context(|| format!("Failed to call chat completions API at {}", self.base_url))?;
+
+        let response_data: serde_json::Value = response.json().await
+            .context("Failed to parse chat completions response")?;
+
+        if let Some(error) = response_data.get("error") {
+            let message = error["message"].as_str().or(error.as_str()).unwrap_or("unknown error");
+            anyhow::bail!("Chat completions API error: {}", message);
+        }
+
+        let message = &response_data["choices"][0]["message"];
+        if message.is_null() {
+            anyhow::bail!("Invalid chat completions response format");
+        }
+
+        let mut chat_response = ChatResponse {
+            text: message["content"].as_str().unwrap_or_default().to_string(),
+            tool_calls: Vec::new(),
+        };
+
+        for (index, call) in message["tool_calls"].as_array().into_iter().flatten().enumerate() {
+            let name = call["function"]["name"].as_str().context("Tool call without a function name")?;
+            // The spec sends arguments as a JSON string; some servers send an object.
+            let arguments = match &call["function"]["arguments"] {
+                serde_json::Value::String(raw) if raw.trim().is_empty() => serde_json::json!({}),
+                serde_json::Value::String(raw) => serde_json::from_str(raw)
+                    .with_context(|| format!("Invalid arguments for tool call {}: {}", name, raw))?,
+                serde_json::Value::Null => serde_json::json!({}),
+                arguments => arguments.clone(),
+            };
+            chat_response.tool_calls.push(ToolCall {
+                id: call["id"].as_str().map(str::to_string).unwrap_or_else(|| format!("call_{}", index)),
+                name: name.to_string(),
+                arguments,
+            });
+        }
+
+        Ok(chat_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ToolSpec;
+    use crate::test_support::StubServer;
+
+    fn request<'a>(messages: &'a [Message], tools: &'a [ToolSpec]) -> ChatRequest<'a> {
+        ChatRequest { system: "You are a test.", messages, tools }
+    }
+
+    #[tokio::test]
+    async fn parses_tool_calls_with_string_and_object_arguments() {
+        let server = StubServer::start(vec![(200, r#"{
+            "choices": [{"message": {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_a", "type": "function", "function": {"name": "get_weather", "arguments": "{\"days\": 2}"}},
+                {"type": "function", "function": {"name": "get_news", "arguments": {"topic": "sports"}}}
+            ]}}]
+        }"#)])
+        .await;
+        let provider = OpenAiProvider::new(format!("{}/v1/", server.url), "test-model").with_api_key("secret");
+        let messages = [Message::User("Weather and news?".to_string())];
+        let tools = [ToolSpec {
+            name: "get_weather".to_string(),
+            description: "Weather".to_string(),
+            parameters: serde_json::json!({"type": "object", "properties": {}}),
+        }];
+
+        let response = provider.chat(&request(&messages, &tools)).await.unwrap();
+
+        assert_eq!(response.text, "");
+        assert_eq!(response.tool_calls.len(), 2);
+        assert_eq!(response.tool_calls[0].id, "call_a");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"days": 2}));
+        assert_eq!(response.tool_calls[1].name, "get_news");
+        assert_eq!(response.tool_calls[1].id, "call_1");
+        assert_eq!(response.tool_calls[1].arguments, serde_json::json!({"topic": "sports"}));
+
+        let sent = &server.requests()[0];
+        assert_eq!(sent.method, "POST");
+        assert_eq!(sent.path, "/v1/chat/completions");
+        assert_eq!(sent.header("authorization"), Some("Bearer secret"));
+        let body = sent.json();
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "Weather and news?");
+        assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+    }
+
+    #[tokio::test]
+    async fn reports_error_bodies() {
+        let server = StubServer::start(vec![(401, r#"{"error": {"message": "Incorrect API key provided"}}"#)]).await;
+        let provider = OpenAiProvider::new(&server.url, "test-model");
+        let messages = [Message::User("Hi".to_string())];
+
+        let error = provider.chat(&request(&messages, &[])).await.unwrap_err();
+
+        assert!(format!("{:#}", error).contains("Incorrect API key provided"), "{:#}", error);
+        assert!(server.requests()[0].header("authorization").is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_tool_results_back_with_their_call_ids() {
+        let server = StubServer::start(vec![(200, r#"{"choices": [{"message": {"content": "Sunny."}}]}"#)]).await;
+        let provider = OpenAiProvider::new(&server.url, "test-model");
+        let call = ToolCall { id: "call_0".to_string(), name: "get_weather".to_string(), arguments: serde_json::json!({}) };
+        let messages = [
+            Message::User("Weather?".to_string()),
+            Message::Assistant { text: String::new(), tool_calls: vec![call] },
+            Message::ToolResult(crate::llm::ToolResult {
+                call_id: "call_0".to_string(),
+                name: "get_weather".to_string(),
+                content: "Sunny".to_string(),
+                is_error: false,
+            }),
+        ];
+
+        let response = provider.chat(&request(&messages, &[])).await.unwrap();
+
+        assert_eq!(response.text, "Sunny.");
+        let body = server.requests()[0].json();
+        assert_eq!(body["messages"][2]["content"], serde_json::Value::Null);
+        assert_eq!(body["messages"][2]["tool_calls"][0]["function"]["arguments"], "{}");
+        assert_eq!(body["messages"][3]["role"], "tool");
+        assert_eq!(body["messages"][3]["tool_call_id"], "call_0");
+    }
+}
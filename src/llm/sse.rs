@@ -0,0 +1,141 @@
+use anyhow::{Result, Context};
+use futures::stream::{self, BoxStream, StreamExt};
+
+/// One server-sent event.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Splits an HTTP response body into server-sent events.
+pub(crate) fn events(response: reqwest::Response) -> BoxStream<'static, Result<SseEvent>> {
+    let state = (response.bytes_stream().boxed(), Vec::new(), false);
+
+    stream::unfold(state, |(mut body, mut buffer, mut finished)| async move {
+        loop {
+            if let Some(event) = next_event(&mut buffer, finished) {
+                return Some((Ok(event), (body, buffer, finished)));
+            }
+            if finished {
+                return None;
+            }
+
+            match body.next().await {
+                Some(Ok(chunk)) => push_chunk(&mut buffer, &chunk),
+                Some(Err(e)) => {
+                    let error = Err(e).context("Failed to read event stream");
+                    return Some((error, (body, buffer, true)));
+                }
+                None => finished = true,
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Buffers raw bytes so multi-byte characters split across chunks survive;
+/// CRs are dropped to normalize line endings.
+fn push_chunk(buffer: &mut Vec<u8>, chunk: &[u8]) {
+    buffer.extend(chunk.iter().filter(|&&byte| byte != b'\r'));
+}
+
+/// Pops the next complete event off `buffer`. At end of stream a trailing
+/// event without the terminating blank line is accepted too.
+fn next_event(buffer: &mut Vec<u8>, finished: bool) -> Option<SseEvent> {
+    loop {
+        let block = match buffer.windows(2).position(|window| window == b"\n\n") {
+            Some(end) => {
+                let block = String::from_utf8_lossy(&buffer[..end]).into_owned();
+                buffer.drain(..end + 2);
+                block
+            }
+            None if finished && !buffer.is_empty() => String::from_utf8_lossy(&std::mem::take(buffer)).into_owned(),
+            None => return None,
+        };
+
+        let mut event = SseEvent::default();
+        let mut data_lines = Vec::new();
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("event:") {
+                event.event = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data_lines.push(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+
+        // Comment-only blocks (keep-alives) carry no data.
+        if !data_lines.is_empty() {
+            event.data = data_lines.join("\n");
+            return Some(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `chunks` one at a time, collecting every event completed so far.
+    fn decode(chunks: &[&[u8]]) -> Vec<SseEvent> {
+        let mut buffer = Vec::new();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            push_chunk(&mut buffer, chunk);
+            events.extend(std::iter::from_fn(|| next_event(&mut buffer, false)));
+        }
+        events.extend(std::iter::from_fn(|| next_event(&mut buffer, true)));
+        events
+    }
+
+    #[test]
+    fn joins_events_split_across_chunks() {
+        let events = decode(&[b"event: message_start\nda", b"ta: {\"a\":", b"1}\n", b"\ndata: two\n\n"]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.as_deref(), Some("message_start"));
+        assert_eq!(events[0].data, r#"{"a":1}"#);
+        assert_eq!(events[1].event, None);
+        assert_eq!(events[1].data, "two");
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let text = "data: caf\u{e9} \u{1f326}\n\n".as_bytes();
+        let (first, rest) = text.split_at(10);
+        let (second, third) = rest.split_at(5);
+
+        let events = decode(&[first, second, third]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "caf\u{e9} \u{1f326}");
+    }
+
+    #[test]
+    fn accepts_crlf_line_endings() {
+        let events = decode(&[b"event: ping\r\ndata: a\r\n\r", b"\ndata: b\r\ndata: c\r\n\r\n"]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.as_deref(), Some("ping"));
+        assert_eq!(events[0].data, "a");
+        assert_eq!(events[1].data, "b\nc");
+    }
+
+    #[test]
+    fn skips_keep_alive_comments() {
+        let events = decode(&[b": keep-alive\n\n", b": ping\n\ndata: real\n\n"]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "real");
+    }
+
+    #[test]
+    fn accepts_a_trailing_event_without_blank_line() {
+        let mut buffer = Vec::new();
+        push_chunk(&mut buffer, b"data: [DONE]");
+
+        assert!(next_event(&mut buffer, false).is_none());
+        assert_eq!(next_event(&mut buffer, true).unwrap().data, "[DONE]");
+        assert!(next_event(&mut buffer, true).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use crate::LocationInfo;
+use async_trait::async_trait;
+use crate::{LocationInfo, llm::Tool};
 
 pub struct LocationDetails {
     location_info: LocationInfo,
@@ -12,11 +13,27 @@ impl LocationDetails {
 
     pub async fn get_location_info(&self) -> Result<String> {
         Ok(format!(
-            "Location Details:\n- Name: {}\n- Coordinates: {:.4}°N, {:.4}°E\n- Country: {}\n- Ready to provide weather, news, and alerts for this location.",
+            "Location Details:\n- Name: {}\n- Region: {}\n- Coordinates: {:.4}°N, {:.4}°E\n- Country: {}\n- Ready to provide weather, news, and alerts for this location.",
             self.location_info.name,
+            self.location_info.admin1.as_deref().unwrap_or("Unknown"),
             self.location_info.latitude,
             self.location_info.longitude,
             self.location_info.country.as_deref().unwrap_or("Unknown")
         ))
     }
-} 
\ No newline at end of file
+}
+
+#[async_trait]
+impl Tool for LocationDetails {
+    fn name(&self) -> &str {
+        "get_location_info"
+    }
+
+    fn description(&self) -> &str {
+        "Get location details"
+    }
+
+    async fn call(&self, _args: serde_json::Value) -> Result<String> {
+        self.get_location_info().await
+    }
+}
@@ -1,25 +1,115 @@
 use anyhow::{Result, Context};
-use clap::Parser;
-use tracing_subscriber;
+use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use std::collections::BTreeMap;
 use std::io::Write;
-use std::env;
+use std::path::PathBuf;
 
-use rust_agent_demo::{geocoding::GeocodingService, llm::LLMProcessor};
+use rust_agent_demo::{
+    config::Config,
+    geocoding::GeocodingService,
+    llm::{self, conversation::DEFAULT_HISTORY_WINDOW, AgentEvent, Conversation, LLMProcessor, ProviderOptions, ToolRegistry, DEFAULT_MAX_STEPS},
+    units::{PrecipitationUnit, TemperatureUnit, Units, WindSpeedUnit},
+    watch::{WatchLocation, WatchTarget, Watcher},
+    LocationInfo,
+};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
-    #[arg(short, long)]
-    location: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(short, long, required = true)]
+    location: Option<String>,
     
     #[arg(short, long)]
     country: Option<String>,
     
+    /// LLM backend to use (gemini, claude, openai, ollama)
+    #[arg(short, long)]
+    provider: Option<String>,
+
+    /// Shorthand for --provider gemini
     #[arg(long)]
     gemini: bool,
     
+    /// Shorthand for --provider claude
     #[arg(long)]
     claude: bool,
+
+    /// Model name override for the selected provider
+    #[arg(long)]
+    model: Option<String>,
+
+    /// API base URL override, e.g. http://localhost:8000/v1 for a local OpenAI-compatible server
+    #[arg(long)]
+    base_url: Option<String>,
+
+    /// Environment variable holding the API key for --provider openai
+    #[arg(long)]
+    api_key_env: Option<String>,
+
+    /// Number of recent exchanges, including the current one, sent to the model
+    #[arg(long, default_value_t = DEFAULT_HISTORY_WINDOW)]
+    history: usize,
+
+    /// Maximum model calls per question, including tool round trips
+    #[arg(long, default_value_t = DEFAULT_MAX_STEPS)]
+    max_steps: usize,
+
+    /// TOML configuration file
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Unit system for weather output (metric, imperial)
+    #[arg(long, global = true)]
+    units: Option<Units>,
+
+    /// Temperature unit (celsius, fahrenheit); overrides --units
+    #[arg(long, global = true)]
+    temperature_unit: Option<TemperatureUnit>,
+
+    /// Wind speed unit (kmh, ms, mph, kn); overrides --units
+    #[arg(long, global = true)]
+    wind_speed_unit: Option<WindSpeedUnit>,
+
+    /// Precipitation unit (mm, inch); overrides --units
+    #[arg(long, global = true)]
+    precipitation_unit: Option<PrecipitationUnit>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Poll weather alerts and print new, escalated and cleared ones
+    Watch(WatchArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct WatchArgs {
+    /// Location to watch; repeat for several. Replaces [watch] locations from the config
+    #[arg(short, long)]
+    location: Vec<String>,
+
+    /// Country code used when geocoding --location values
+    #[arg(short, long)]
+    country: Option<String>,
+
+    /// Sink from the [sinks] config section to notify for --location values; repeatable
+    #[arg(long)]
+    notify: Vec<String>,
+
+    /// Minutes between polls
+    #[arg(long)]
+    interval: Option<u64>,
+
+    /// File remembering which alerts were already reported
+    #[arg(long)]
+    state: Option<PathBuf>,
+
+    /// Poll once and exit, e.g. when run from cron
+    #[arg(long)]
+    once: bool,
 }
 
 #[tokio::main]
@@ -27,45 +117,66 @@ async fn main() -> Result<()> {
     tracing_subscriber::fmt().init();
 
     let args = Args::parse();
-    
-    if args.gemini && env::var("GEMINI_API_KEY").is_err() {
-        eprintln!("GEMINI_API_KEY environment variable not set");
-        eprintln!("Set it with: export GEMINI_API_KEY='your-key-here'");
-        eprintln!("Get your key from: https://makersuite.google.com/app/apikey");
-        std::process::exit(1);
+
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    if let Some(units) = args.units {
+        config.units = units;
     }
-    
-    if args.claude && env::var("ANTHROPIC_API_KEY").is_err() {
-        eprintln!("ANTHROPIC_API_KEY environment variable not set");
-        eprintln!("Set it with: export ANTHROPIC_API_KEY='your-key-here'");
-        std::process::exit(1);
+    if let Some(temperature) = args.temperature_unit {
+        config.units.temperature = temperature;
+    }
+    if let Some(wind_speed) = args.wind_speed_unit {
+        config.units.wind_speed = wind_speed;
+    }
+    if let Some(precipitation) = args.precipitation_unit {
+        config.units.precipitation = precipitation;
     }
 
-    if !args.gemini && !args.claude {
-        eprintln!("Please specify either --gemini or --claude flag");
-        eprintln!("Example: cargo run -- --location 'New York' --gemini");
-        std::process::exit(1);
+    if let Some(Command::Watch(watch)) = &args.command {
+        return run_watch(watch, config).await;
     }
     
-    eprintln!("Looking up coordinates for '{}'...", args.location);
-    let location_info = GeocodingService::get_coordinates(
-        &args.location, 
-        args.country.as_deref()
-    ).await.context("Failed to get location coordinates")?;
-    
-    eprintln!("Found location: {} at coordinates ({:.4}, {:.4})", 
-             location_info.name, location_info.latitude, location_info.longitude);
+    let provider_name = match (&args.provider, args.gemini, args.claude) {
+        (Some(name), _, _) => name.clone(),
+        (None, true, _) => "gemini".to_string(),
+        (None, false, true) => "claude".to_string(),
+        (None, false, false) => {
+            eprintln!("Please specify a provider with --provider <{}>, or --gemini / --claude", llm::PROVIDERS.join("|"));
+            eprintln!("Example: cargo run -- --location 'New York' --gemini");
+            std::process::exit(1);
+        }
+    };
+
+    let provider_options = ProviderOptions {
+        model: args.model.clone(),
+        base_url: args.base_url.clone(),
+        api_key_env: args.api_key_env.clone(),
+    };
+
+    let provider = match llm::provider_from_name(&provider_name, &provider_options) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    };
     
-    if let Some(country) = &location_info.country {
-        eprintln!("Country: {}", country);
-    }
+    let location = args.location.as_deref().context("--location is required")?;
+    let location_info = look_up(location, args.country.as_deref()).await?;
 
-    let processor = LLMProcessor::new(location_info.clone());
+    eprintln!("Units: {}", config.units);
+
+    let tools = ToolRegistry::with_defaults(&location_info, &config);
+    let processor = LLMProcessor::with_tools(location_info.clone(), provider, tools).with_max_steps(args.max_steps);
     
-    let llm_type = if args.gemini { "Gemini" } else { "Claude" };
-    println!("\nLocation Agent for: {} (using {})", location_info.name, llm_type);
+    println!("\nLocation Agent for: {} (using {})", location_info.name, processor.provider().name());
     println!("Ask me anything about this location!");
-    println!("Type 'quit' to exit.\n");
+    println!("Type 'reset' to forget the conversation so far, 'quit' to exit.\n");
+
+    let mut conversation = Conversation::with_history_window(args.history);
     
     loop {
         print!("You: ");
@@ -83,12 +194,96 @@ async fn main() -> Result<()> {
         if input.is_empty() {
             continue;
         }
+
+        if input.to_lowercase() == "reset" {
+            conversation.clear();
+            println!("Conversation cleared.\n");
+            continue;
+        }
         
-        match processor.process_query(input, args.gemini, args.claude).await {
-            Ok(response) => println!("\nAgent: {}\n", response),
-            Err(e) => println!("\nAgent: Error: {}\n", e),
+        print!("\nAgent: ");
+        std::io::stdout().flush().unwrap();
+
+        let mut events = std::pin::pin!(processor.process_turn_stream(&mut conversation, input));
+        let mut after_tools = false;
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(AgentEvent::TextDelta(delta)) => {
+                    if after_tools {
+                        println!();
+                        after_tools = false;
+                    }
+                    print!("{}", delta);
+                    std::io::stdout().flush().unwrap();
+                }
+                Ok(AgentEvent::ToolCall(call)) => eprintln!("\n[calling {}]", call.name),
+                Ok(AgentEvent::ToolResult(_)) => after_tools = true,
+                Err(e) => print!("Error: {:#}", e),
+            }
         }
+        println!("\n");
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+async fn look_up(location: &str, country: Option<&str>) -> Result<LocationInfo> {
+    eprintln!("Looking up coordinates for '{}'...", location);
+    let location_info = GeocodingService::get_coordinates(location, country)
+        .await
+        .context("Failed to get location coordinates")?;
+
+    eprintln!("Found location: {} at coordinates ({:.4}, {:.4})", 
+             location_info.name, location_info.latitude, location_info.longitude);
+
+    if let Some(country) = &location_info.country {
+        eprintln!("Country: {}", country);
+    }
+    Ok(location_info)
+}
+
+async fn run_watch(args: &WatchArgs, mut config: Config) -> Result<()> {
+    if !args.location.is_empty() {
+        config.watch.locations = args.location
+            .iter()
+            .map(|name| WatchLocation {
+                name: name.clone(),
+                country: args.country.clone(),
+                notify: args.notify.clone(),
+            })
+            .collect();
+    }
+    if let Some(interval) = args.interval {
+        config.watch.interval_minutes = interval;
+    }
+    if let Some(state) = &args.state {
+        config.watch.state_file = state.clone();
+    }
+    if config.watch.locations.is_empty() {
+        anyhow::bail!("No locations to watch; pass --location or add [[watch.locations]] to the config");
+    }
+
+    let mut sinks = BTreeMap::new();
+    for (name, sink) in &config.sinks {
+        sinks.insert(name.as_str(), sink.build(name)?);
+    }
+
+    let mut targets = Vec::new();
+    for location in &config.watch.locations {
+        let sinks = location.notify
+            .iter()
+            .map(|name| sinks.get(name.as_str()).cloned().with_context(|| format!("Unknown sink '{}' for {}", name, location.name)))
+            .collect::<Result<Vec<_>>>()?;
+        let location_info = look_up(&location.name, location.country.as_deref()).await?;
+        targets.push(WatchTarget { location_info, sinks });
+    }
+
+    eprintln!(
+        "Watching alerts for {} every {} minutes (state in {})",
+        targets.iter().map(|target| target.location_info.name.as_str()).collect::<Vec<_>>().join(", "),
+        config.watch.interval_minutes,
+        config.watch.state_file.display()
+    );
+    let mut watcher = Watcher::new(targets, config.units, config.alerts, config.watch)?;
+    watcher.run(args.once).await
+}
@@ -0,0 +1,289 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::{
+    LocationInfo, MARINE_API,
+    llm::Tool,
+    units::{PrecipitationUnit, Units},
+    weather::{OpenMeteoError, format::{compass_direction, format_value, render_table, Column}},
+};
+
+const CURRENT_VARIABLES: &str = "wave_height,wave_direction,wave_period,swell_wave_height,swell_wave_direction,swell_wave_period,sea_surface_temperature";
+const DAILY_VARIABLES: &str = "wave_height_max,wave_direction_dominant,wave_period_max,swell_wave_height_max,swell_wave_period_max";
+const FORECAST_DAYS: u8 = 3;
+
+/// Open-Meteo `/v1/marine` response. Grid cells without sea come back with
+/// every value `null` rather than an error.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarineData {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub current: Option<MarineCurrent>,
+    #[serde(default)]
+    pub current_units: BTreeMap<String, String>,
+    #[serde(default)]
+    pub daily: Option<MarineDaily>,
+    #[serde(default)]
+    pub daily_units: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarineCurrent {
+    pub time: String,
+    pub wave_height: Option<f64>,
+    pub wave_direction: Option<f64>,
+    pub wave_period: Option<f64>,
+    pub swell_wave_height: Option<f64>,
+    pub swell_wave_direction: Option<f64>,
+    pub swell_wave_period: Option<f64>,
+    pub sea_surface_temperature: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarineDaily {
+    pub time: Vec<String>,
+    #[serde(default)]
+    pub wave_height_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wave_direction_dominant: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wave_period_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub swell_wave_height_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub swell_wave_period_max: Vec<Option<f64>>,
+}
+
+impl MarineData {
+    pub fn current_unit(&self, variable: &str) -> &str {
+        self.current_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+
+    pub fn daily_unit(&self, variable: &str) -> &str {
+        self.daily_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Whether the model has any sea state for this point; false inland.
+    pub fn has_data(&self) -> bool {
+        let current = self.current.as_ref().is_some_and(|current| {
+            current.wave_height.is_some() || current.swell_wave_height.is_some() || current.sea_surface_temperature.is_some()
+        });
+        let daily = self.daily.as_ref().is_some_and(|daily| {
+            daily.wave_height_max.iter().chain(&daily.swell_wave_height_max).any(Option::is_some)
+        });
+        current || daily
+    }
+}
+
+/// Waves, swell and sea-surface temperature from the Open-Meteo marine model.
+pub struct Marine {
+    location_info: LocationInfo,
+    units: Units,
+    base_url: String,
+}
+
+impl Marine {
+    pub fn new(location_info: LocationInfo) -> Self {
+        Self {
+            location_info,
+            units: Units::default(),
+            base_url: MARINE_API.to_string(),
+        }
+    }
+
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Sends wave and sea-temperature requests to `base_url`, the full
+    /// `/v1/marine` path, rather than Open-Meteo's marine API.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub async fn get_marine_data(&self) -> Result<MarineData> {
+        // Wave heights follow the precipitation setting: inches mean feet here.
+        let length_unit = match self.units.precipitation {
+            PrecipitationUnit::Mm => "metric",
+            PrecipitationUnit::Inch => "imperial",
+        };
+        let url = format!(
+            "{base}?latitude={lat}&longitude={lon}&current={current}&daily={daily}&forecast_days={days}&timezone=auto{units}&length_unit={length_unit}",
+            base = self.base_url,
+            lat = self.location_info.latitude,
+            lon = self.location_info.longitude,
+            current = CURRENT_VARIABLES,
+            daily = DAILY_VARIABLES,
+            days = FORECAST_DAYS,
+            units = self.units.query_params(),
+            length_unit = length_unit
+        );
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("User-Agent", "multi-tool-agent/1.0")
+            .send()
+            .await
+            .context("Failed to fetch marine data")?;
+
+        if !response.status().is_success() {
+            let error: OpenMeteoError = response
+                .json()
+                .await
+                .context("Failed to read marine error response")?;
+            anyhow::bail!("Open-Meteo rejected the marine request: {}", error.reason);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse marine response")
+    }
+
+    pub async fn get_marine(&self) -> Result<String> {
+        let data = self.get_marine_data().await?;
+        Ok(render_marine(&self.location_info, &data))
+    }
+}
+
+/// Current sea state followed by a short daily outlook, or a note that the
+/// location has no marine data.
+pub fn render_marine(location_info: &LocationInfo, data: &MarineData) -> String {
+    if !data.has_data() {
+        return format!(
+            "No marine data for inland location {} ({:.2}, {:.2})",
+            location_info.name, location_info.latitude, location_info.longitude
+        );
+    }
+
+    let mut output = format!("Marine conditions for {}:\n", location_info.name);
+
+    if let Some(current) = &data.current {
+        let direction = |degrees: Option<f64>| degrees.map(|degrees| format!(" from {}", compass_direction(degrees))).unwrap_or_default();
+        if current.wave_height.is_some() {
+            output.push_str(&format!(
+                "- Waves: {}{}, period {}\n",
+                format_value(current.wave_height, data.current_unit("wave_height")),
+                direction(current.wave_direction),
+                format_value(current.wave_period, data.current_unit("wave_period"))
+            ));
+        }
+        if current.swell_wave_height.is_some() {
+            output.push_str(&format!(
+                "- Swell: {}{}, period {}\n",
+                format_value(current.swell_wave_height, data.current_unit("swell_wave_height")),
+                direction(current.swell_wave_direction),
+                format_value(current.swell_wave_period, data.current_unit("swell_wave_period"))
+            ));
+        }
+        if current.sea_surface_temperature.is_some() {
+            output.push_str(&format!(
+                "- Sea surface temperature: {}\n",
+                format_value(current.sea_surface_temperature, data.current_unit("sea_surface_temperature"))
+            ));
+        }
+    }
+
+    if let Some(daily) = data.daily.as_ref().filter(|daily| !daily.time.is_empty()) {
+        let rows: Vec<usize> = (0..daily.time.len()).collect();
+        let numeric = |header, series: &[Option<f64>], variable| {
+            Column::numeric(header, series, &rows, data.daily_unit(variable))
+        };
+        let columns = [
+            Column::text("Date", daily.time.clone()),
+            numeric("Waves max", &daily.wave_height_max, "wave_height_max"),
+            Column::optional_text(
+                "From",
+                rows.iter()
+                    .map(|&index| {
+                        daily.wave_direction_dominant
+                            .get(index)
+                            .copied()
+                            .flatten()
+                            .map_or("n/a", compass_direction)
+                            .to_string()
+                    })
+                    .collect(),
+                !daily.wave_direction_dominant.is_empty(),
+            ),
+            numeric("Period max", &daily.wave_period_max, "wave_period_max"),
+            numeric("Swell max", &daily.swell_wave_height_max, "swell_wave_height_max"),
+            numeric("Swell period", &daily.swell_wave_period_max, "swell_wave_period_max"),
+        ];
+        output.push('\n');
+        output.push_str(&render_table(&columns));
+    }
+
+    output
+}
+
+#[async_trait]
+impl Tool for Marine {
+    fn name(&self) -> &str {
+        "get_marine_forecast"
+    }
+
+    fn description(&self) -> &str {
+        "Get wave height, swell period and sea-surface temperature for coastal locations, with a 3-day outlook"
+    }
+
+    async fn call(&self, _args: serde_json::Value) -> Result<String> {
+        self.get_marine().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{StubServer, location};
+
+    #[tokio::test]
+    async fn renders_sea_state_in_feet_for_imperial_units() {
+        let server = StubServer::start(vec![(200, r#"{
+            "latitude": 21.3, "longitude": -157.9, "timezone": "Pacific/Honolulu",
+            "current_units": {"wave_height": "ft", "wave_period": "s", "swell_wave_height": "ft", "swell_wave_period": "s", "sea_surface_temperature": "°F"},
+            "current": {"time": "2025-01-10T12:00", "wave_height": 6.2, "wave_direction": 315.0, "wave_period": 11.5,
+                        "swell_wave_height": 4.9, "swell_wave_direction": 300.0, "swell_wave_period": 14.0, "sea_surface_temperature": 76.1},
+            "daily_units": {"wave_height_max": "ft", "wave_period_max": "s", "swell_wave_height_max": "ft", "swell_wave_period_max": "s"},
+            "daily": {"time": ["2025-01-10", "2025-01-11"], "wave_height_max": [7.5, 9.1], "wave_direction_dominant": [315.0, 0.0],
+                      "wave_period_max": [12.0, 13.5], "swell_wave_height_max": [5.2, 8.0], "swell_wave_period_max": [14.0, 16.0]}
+        }"#)])
+        .await;
+        let marine = Marine::new(location("Honolulu", 21.3, -157.9))
+            .with_units(Units::imperial())
+            .with_base_url(format!("{}/v1/marine", server.url));
+
+        let output = marine.get_marine().await.unwrap();
+
+        assert!(output.starts_with("Marine conditions for Honolulu:"), "{}", output);
+        assert!(output.contains("- Waves: 6.2 ft from NW, period 11.5 s"), "{}", output);
+        assert!(output.contains("- Swell: 4.9 ft from WNW, period 14 s"), "{}", output);
+        assert!(output.contains("- Sea surface temperature: 76.1°F"), "{}", output);
+        assert!(output.contains("2025-01-11"), "{}", output);
+        let path = &server.requests()[0].path;
+        assert!(path.starts_with("/v1/marine?latitude=21.3&longitude=-157.9"), "{}", path);
+        assert!(path.ends_with("&length_unit=imperial"), "{}", path);
+    }
+
+    #[tokio::test]
+    async fn reports_inland_locations() {
+        let server = StubServer::start(vec![(200, r#"{
+            "latitude": 48.1, "longitude": 11.6, "timezone": "Europe/Berlin",
+            "current": {"time": "2025-01-10T12:00", "wave_height": null, "swell_wave_height": null, "sea_surface_temperature": null},
+            "daily": {"time": ["2025-01-10"], "wave_height_max": [null], "swell_wave_height_max": [null]}
+        }"#)])
+        .await;
+        let marine = Marine::new(location("Munich", 48.137, 11.575)).with_base_url(&server.url);
+
+        let output = marine.get_marine().await.unwrap();
+
+        assert_eq!(output, "No marine data for inland location Munich (48.14, 11.57)");
+        assert!(server.requests()[0].path.ends_with("&length_unit=metric"));
+    }
+}
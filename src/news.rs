@@ -1,98 +1,412 @@
 use anyhow::{Result, Context};
-use crate::{LocationInfo, NEWS_API, NEWS_API_KEY};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use futures::future;
+use serde::{Deserialize, Serialize};
+use crate::{LocationInfo, NEWS_API_KEY, llm::Tool};
 use std::env;
 
+pub mod gnews;
+pub mod merge;
+pub mod newsapi;
+pub mod planner;
+pub mod rss;
+
+pub use gnews::GNewsProvider;
+pub use merge::merge;
+pub use newsapi::NewsApiProvider;
+pub use planner::{plan_queries, NewsQuery, NewsScope};
+pub use rss::{NewsFeed, RssProvider};
+
+const DEFAULT_COUNT: usize = 5;
+const MAX_COUNT: usize = 20;
+const DEFAULT_MAX_AGE_HOURS: u64 = 72;
+/// Descriptions are cut to keep the tool result short.
+const MAX_DESCRIPTION_CHARS: usize = 160;
+
+/// A news article, independent of the service it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    pub title: String,
+    /// Publication name, e.g. "BBC News".
+    pub source: String,
+    pub author: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub url: String,
+    pub description: Option<String>,
+}
+
+impl Article {
+    /// Whether the title or description mentions `keyword`, ignoring case.
+    pub fn mentions(&self, keyword: &str) -> bool {
+        let keyword = keyword.to_lowercase();
+        self.title.to_lowercase().contains(&keyword)
+            || self.description.as_deref().is_some_and(|description| description.to_lowercase().contains(&keyword))
+    }
+}
+
+/// Parameters of a news request, also what the news tool accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsRequest {
+    /// A headline category such as "sports", or search words such as
+    /// "transport strikes".
+    pub topic: Option<String>,
+    /// First and last day of publication, inclusive.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Publications to rank first, matched against the source name or the
+    /// article's domain. NewsAPI also limits its queries to them.
+    pub sources: Vec<String>,
+    /// ISO 639-1 code, e.g. "en".
+    pub language: Option<String>,
+    /// Number of articles, 1 to 20.
+    pub count: usize,
+}
+
+impl Default for NewsRequest {
+    fn default() -> Self {
+        Self { topic: None, from: None, to: None, sources: Vec::new(), language: None, count: DEFAULT_COUNT }
+    }
+}
+
+impl NewsRequest {
+    /// Reads the news tool arguments. Malformed dates are errors so the
+    /// model can correct them; an unusable language is ignored.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let date = |field: &str| -> Result<Option<NaiveDate>> {
+            args[field]
+                .as_str()
+                .map(|value| {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .with_context(|| format!("{} must be YYYY-MM-DD, got '{}'", field, value))
+                })
+                .transpose()
+        };
+        let (from, to) = (date("from")?, date("to")?);
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            anyhow::bail!("from ({}) is after to ({})", from, to);
+        }
+
+        let language = args["language"].as_str().map(str::to_lowercase);
+        let language = match language {
+            Some(code) if code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase()) => Some(code),
+            Some(code) => {
+                tracing::debug!("Ignoring unknown news language {}", code);
+                None
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            topic: args["topic"].as_str().map(str::trim).filter(|topic| !topic.is_empty()).map(str::to_string),
+            from,
+            to,
+            sources: args["sources"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|source| source.as_str())
+                .map(str::to_string)
+                .collect(),
+            language,
+            count: args["count"]
+                .as_u64()
+                .map(|count| (count as usize).clamp(1, MAX_COUNT))
+                .unwrap_or(DEFAULT_COUNT),
+        })
+    }
+
+    /// Start of `from` in UTC.
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.from.map(|from| from.and_time(NaiveTime::MIN).and_utc())
+    }
+
+    /// End of `to` in UTC, exclusive.
+    pub fn until(&self) -> Option<DateTime<Utc>> {
+        self.to.and_then(|to| to.succ_opt()).map(|end| end.and_time(NaiveTime::MIN).and_utc())
+    }
+
+    /// Undated articles are given the benefit of the doubt.
+    pub fn in_window(&self, published_at: Option<DateTime<Utc>>) -> bool {
+        published_at.is_none_or(|published_at| {
+            self.since().is_none_or(|since| published_at >= since) && self.until().is_none_or(|until| published_at < until)
+        })
+    }
+
+    /// Whether the article mentions any topic word, ignoring a plural "s",
+    /// for providers that cannot search. True when there is no topic.
+    pub fn matches_topic(&self, article: &Article) -> bool {
+        let Some(topic) = &self.topic else {
+            return true;
+        };
+        let text = format!("{} {}", article.title, article.description.as_deref().unwrap_or_default()).to_lowercase();
+        topic
+            .to_lowercase()
+            .split_whitespace()
+            .map(|word| if word.len() > 3 { word.trim_end_matches('s') } else { word })
+            .any(|word| text.contains(word))
+    }
+
+    /// Whether the article comes from one of the preferred sources.
+    pub fn prefers(&self, article: &Article) -> bool {
+        let source = article.source.to_lowercase();
+        let host = reqwest::Url::parse(&article.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        self.sources.iter().map(|preferred| preferred.trim().to_lowercase()).any(|preferred| {
+            !preferred.is_empty() && (source.contains(&preferred) || host.ends_with(&preferred))
+        })
+    }
+}
+
+/// An article and how closely it relates to the location asked about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedArticle {
+    pub scope: NewsScope,
+    pub article: Article,
+}
+
+/// A source of news articles for a location.
+#[async_trait]
+pub trait NewsProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Up to roughly `request.count` articles, narrowed by the request as
+    /// far as the provider supports; the merge applies the rest.
+    async fn fetch(&self, location_info: &LocationInfo, request: &NewsRequest) -> Result<Vec<ScopedArticle>>;
+}
+
+/// `[news]` section of the config file.
+///
+/// ```toml
+/// [news]
+/// max_age_hours = 48
+///
+/// [[news.feeds]]
+/// url = "https://www3.nhk.or.jp/rss/news/cat0.xml"
+/// countries = ["JP"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewsConfig {
+    /// Feed items older than this are ignored.
+    pub max_age_hours: u64,
+    pub feeds: Vec<NewsFeed>,
+}
+
+impl Default for NewsConfig {
+    fn default() -> Self {
+        Self { max_age_hours: DEFAULT_MAX_AGE_HOURS, feeds: Vec::new() }
+    }
+}
+
+/// Headlines for the location from every configured provider: NewsAPI and
+/// GNews when their keys are set, and the RSS/Atom feeds from the config.
 pub struct News {
     location_info: LocationInfo,
+    providers: Vec<Box<dyn NewsProvider>>,
 }
 
 impl News {
+    /// Starts with the providers whose API keys are set in the environment.
     pub fn new(location_info: LocationInfo) -> Self {
-        Self { location_info }
+        let mut providers: Vec<Box<dyn NewsProvider>> = Vec::new();
+        let news_api_key = env::var("NEWS_API_KEY").unwrap_or_else(|_| NEWS_API_KEY.to_string());
+        if news_api_key != "your_newsapi_key_here" {
+            providers.push(Box::new(NewsApiProvider::new(news_api_key)));
+        }
+        if let Ok(gnews_api_key) = env::var("GNEWS_API_KEY")
+            && !gnews_api_key.is_empty()
+        {
+            providers.push(Box::new(GNewsProvider::new(gnews_api_key)));
+        }
+        Self { location_info, providers }
+    }
+
+    /// Adds the configured RSS/Atom feeds, if any.
+    pub fn with_config(self, config: NewsConfig) -> Self {
+        if config.feeds.is_empty() {
+            return self;
+        }
+        self.with_provider(RssProvider::new(config.feeds, config.max_age_hours))
+    }
+
+    pub fn with_provider(mut self, provider: impl NewsProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
     }
 
     pub async fn get_news(&self) -> Result<String> {
-        println!("DEBUG: get_news() called for {}", self.location_info.name);
-        
-        let api_key = env::var("NEWS_API_KEY").unwrap_or_else(|_| NEWS_API_KEY.to_string());
-        println!("DEBUG: Using API key: {}", if api_key.len() > 10 { &api_key[..10] } else { "short" });
-        
-        if api_key == "your_newsapi_key_here" {
-            println!("DEBUG: Using default API key - this will likely fail");
-            return Ok(format!("News service requires a valid API key. Please set NEWS_API_KEY environment variable or update the constant with your NewsAPI.org key to get news for {}", self.location_info.name));
+        self.get_news_with(&NewsRequest::default()).await
+    }
+
+    /// Queries all providers at once and merges their articles. A provider
+    /// that fails is skipped unless every one of them does.
+    pub async fn get_news_with(&self, request: &NewsRequest) -> Result<String> {
+        tracing::debug!("get_news() called for {} with {:?}", self.location_info.name, request);
+
+        if self.providers.is_empty() {
+            return Ok(format!("News service requires a valid API key or configured news feeds. Please set NEWS_API_KEY or GNEWS_API_KEY environment variable or add [[news.feeds]] to the config file to get news for {}", self.location_info.name));
         }
-        
-        // Try different search strategies
-        let search_queries = vec![
-            self.location_info.name.clone(),
-            format!("{} {}", self.location_info.name, self.location_info.country.as_deref().unwrap_or("")),
-            format!("{} news", self.location_info.name),
-            format!("{} France", self.location_info.name), // For Paris
-        ];
-        
-        let mut news_data = String::new();
-        
-        for query in search_queries {
-            let url = format!(
-                "{base}?q={query}&apiKey={api_key}&pageSize=5&sortBy=publishedAt&language=en",
-                base = NEWS_API, 
-                query = urlencoding::encode(&query),
-                api_key = api_key
-            );
-            
-            println!("DEBUG: Trying query: '{}'", query);
-            println!("DEBUG: URL: {}", url);
-            
-            let client = reqwest::Client::new();
-            let response = client
-                .get(&url)
-                .header("User-Agent", "multi-tool-agent/1.0")
-                .send()
-                .await
-                .context("Failed to fetch news data")?;
-            
-            println!("DEBUG: Response status: {}", response.status());
-            
-            let data = response
-                .text()
-                .await
-                .context("Failed to read news response")?;
-            
-            println!("DEBUG: Response data: {}", &data[..data.len().min(200)]);
-            
-            // Check if we got results
-            if data.contains("\"totalResults\":0") || data.contains("\"articles\":[]") {
-                println!("DEBUG: No results for query '{}', trying next...", query);
-                continue; // Try next query
-            } else {
-                println!("DEBUG: Found results for query '{}'", query);
-                news_data = data;
-                break; // Found results
+
+        let fetches = self.providers.iter().map(|provider| provider.fetch(&self.location_info, request));
+        let mut results = Vec::new();
+        let mut last_error = None;
+        for (provider, result) in self.providers.iter().zip(future::join_all(fetches).await) {
+            match result {
+                Ok(articles) => {
+                    tracing::debug!("{} returned {} articles", provider.name(), articles.len());
+                    results.push(articles);
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping {} news: {:#}", provider.name(), e);
+                    last_error = Some(e);
+                }
             }
         }
-        
-        // If still no results, try a broader search
-        if news_data.is_empty() || news_data.contains("\"totalResults\":0") {
-            let url = format!(
-                "{base}?q=France&apiKey={api_key}&pageSize=5&sortBy=publishedAt&language=en",
-                base = NEWS_API, 
-                api_key = api_key
-            );
-            
-            let client = reqwest::Client::new();
-            let response = client
-                .get(&url)
-                .header("User-Agent", "multi-tool-agent/1.0")
-                .send()
-                .await
-                .context("Failed to fetch news data")?;
-            
-            news_data = response
-                .text()
-                .await
-                .context("Failed to read news response")?;
+        if results.is_empty()
+            && let Some(e) = last_error
+        {
+            return Err(e);
+        }
+
+        let articles = merge(&self.location_info, results, request);
+        let Some(scope) = articles.first().map(|first| first.scope) else {
+            return Ok(format!("No {} found for {}", describe(request), self.location_info.name));
+        };
+        let label = scope_label(&self.location_info, scope);
+        Ok(render_articles(&heading(&self.location_info, request, scope, &label), &articles))
+    }
+}
+
+/// Says which fallback level the headlines come from, so the model does not
+/// present national news as local.
+fn heading(location_info: &LocationInfo, request: &NewsRequest, scope: NewsScope, label: &str) -> String {
+    let news = describe(request);
+    match scope {
+        NewsScope::Local => format!("Latest {} for {}", news, label),
+        NewsScope::Global => format!("No local {} found for {}; showing global headlines instead", news, location_info.name),
+        scope => format!(
+            "No local {} found for {}; showing {} headlines for {} instead",
+            news, location_info.name, scope, label
+        ),
+    }
+}
+
+/// "news", narrowed by topic and period, e.g. "news about strikes from 2026-10-12".
+fn describe(request: &NewsRequest) -> String {
+    let mut news = "news".to_string();
+    if let Some(topic) = &request.topic {
+        news.push_str(&format!(" about {}", topic));
+    }
+    match (request.from, request.to) {
+        (Some(from), Some(to)) => news.push_str(&format!(" from {} to {}", from, to)),
+        (Some(from), None) => news.push_str(&format!(" since {}", from)),
+        (None, Some(to)) => news.push_str(&format!(" until {}", to)),
+        (None, None) => {}
+    }
+    news
+}
+
+/// The place a scope refers to, e.g. the region name for `Regional`.
+fn scope_label(location_info: &LocationInfo, scope: NewsScope) -> String {
+    let label = match scope {
+        NewsScope::Local => Some(&location_info.name),
+        NewsScope::Regional => location_info.admin1.as_ref(),
+        NewsScope::National => location_info.country.as_ref().or(location_info.country_code.as_ref()),
+        NewsScope::Global => None,
+    };
+    label.cloned().unwrap_or_else(|| "worldwide".to_string())
+}
+
+/// Numbered headlines with source and time, each followed by a shortened
+/// description and the link. Articles from a wider scope than the first one
+/// are marked with their scope.
+pub fn render_articles(heading: &str, articles: &[ScopedArticle]) -> String {
+    let Some(first) = articles.first() else {
+        return format!("{}: no articles found", heading);
+    };
+
+    let mut output = format!("{}:\n", heading);
+    for (index, ScopedArticle { scope, article }) in articles.iter().enumerate() {
+        let mut byline = vec![article.source.clone()];
+        if let Some(published_at) = article.published_at {
+            byline.push(published_at.format("%Y-%m-%d %H:%M UTC").to_string());
+        }
+        if *scope != first.scope {
+            byline.push(scope.to_string());
+        }
+        byline.retain(|part| !part.is_empty());
+
+        output.push_str(&format!("{}. {}", index + 1, article.title));
+        if !byline.is_empty() {
+            output.push_str(&format!(" ({})", byline.join(", ")));
         }
-        
-        Ok(format!("Latest news headlines for {}: {}", self.location_info.name, news_data))
+        output.push('\n');
+        if let Some(description) = &article.description {
+            output.push_str(&format!("   {}\n", truncate(description, MAX_DESCRIPTION_CHARS)));
+        }
+        output.push_str(&format!("   {}\n", article.url));
     }
-} 
\ No newline at end of file
+    output
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[async_trait]
+impl Tool for News {
+    fn name(&self) -> &str {
+        "get_news"
+    }
+
+    fn description(&self) -> &str {
+        "Get news headlines for the location, widening to region, country or world when there is no local news. \
+        Pass a topic and dates for targeted questions, e.g. topic \"transport strikes\" with this week's dates."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "topic": {
+                    "type": "string",
+                    "description": "Category (business, entertainment, health, science, sports, technology) or search words, e.g. \"traffic\""
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Earliest publication day, YYYY-MM-DD"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Latest publication day, YYYY-MM-DD"
+                },
+                "sources": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Publications to prefer, by name or domain, e.g. \"BBC News\" or \"reuters.com\""
+                },
+                "language": {
+                    "type": "string",
+                    "description": "ISO 639-1 language code, e.g. \"en\""
+                },
+                "count": {
+                    "type": "integer",
+                    "description": "Number of articles, 1 to 20 (default 5)"
+                }
+            }
+        })
+    }
+
+    async fn call(&self, args: serde_json::Value) -> Result<String> {
+        self.get_news_with(&NewsRequest::from_args(&args)?).await
+    }
+}
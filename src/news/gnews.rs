@@ -0,0 +1,232 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use crate::{LocationInfo, GNEWS_API};
+use super::{Article, NewsProvider, NewsQuery, NewsRequest, ScopedArticle, plan_queries};
+
+/// Countries GNews' `country` parameter accepts.
+const GNEWS_COUNTRIES: &[&str] = &[
+    "au", "br", "ca", "ch", "cn", "de", "eg", "es", "fr", "gb", "gr", "hk", "ie", "il", "in", "it", "jp", "nl",
+    "no", "pe", "ph", "pk", "pt", "ro", "ru", "se", "sg", "tw", "ua", "us",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GNewsResponse {
+    total_articles: u64,
+    articles: Vec<GNewsArticle>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GNewsArticle {
+    title: String,
+    description: Option<String>,
+    url: String,
+    published_at: Option<DateTime<Utc>>,
+    source: GNewsSource,
+}
+
+#[derive(Debug, Deserialize)]
+struct GNewsSource {
+    name: Option<String>,
+}
+
+/// Failures come back as `{"errors": [...]}`, or keyed by parameter as
+/// `{"errors": {"q": "..."}}`.
+#[derive(Debug, Deserialize)]
+struct GNewsError {
+    errors: serde_json::Value,
+}
+
+impl From<GNewsArticle> for Article {
+    fn from(article: GNewsArticle) -> Self {
+        Self {
+            title: article.title,
+            source: article.source.name.unwrap_or_default(),
+            // GNews does not report authors.
+            author: None,
+            published_at: article.published_at,
+            url: article.url,
+            description: article.description.filter(|description| !description.is_empty()),
+        }
+    }
+}
+
+/// Headlines and searches from gnews.io, widening from the place to the
+/// world until a query returns anything.
+pub struct GNewsProvider {
+    api_key: String,
+    base_url: String,
+}
+
+impl GNewsProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self { api_key: api_key.into(), base_url: GNEWS_API.to_string() }
+    }
+
+    /// Overrides the GNews root (`.../api/v4`); `/search` and
+    /// `/top-headlines` are appended to it.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Searches when the query has terms and no category, otherwise asks
+    /// for top headlines, which take both.
+    pub async fn fetch_articles(&self, query: &NewsQuery, request: &NewsRequest) -> Result<Vec<Article>> {
+        let mut url = match (&query.q, &query.category) {
+            (Some(q), None) => format!(
+                "{}/search?q={}&sortby=publishedAt&max={}",
+                self.base_url,
+                urlencoding::encode(q),
+                request.count
+            ),
+            (q, category) => {
+                let mut url = format!(
+                    "{}/top-headlines?category={}&max={}",
+                    self.base_url,
+                    category.as_deref().unwrap_or("general"),
+                    request.count
+                );
+                if let Some(q) = q {
+                    url.push_str(&format!("&q={}", urlencoding::encode(q)));
+                }
+                url
+            }
+        };
+        if let Some(country) = &query.country {
+            url.push_str(&format!("&country={}", country));
+        }
+        if let Some(language) = &request.language {
+            url.push_str(&format!("&lang={}", language));
+        }
+        let time = |time: DateTime<Utc>| urlencoding::encode(&time.to_rfc3339_opts(SecondsFormat::Secs, true)).into_owned();
+        if let Some(since) = request.since() {
+            url.push_str(&format!("&from={}", time(since)));
+        }
+        if let Some(until) = request.until() {
+            url.push_str(&format!("&to={}", time(until)));
+        }
+        tracing::debug!("Trying GNews {} query: {}", query.scope, url);
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .query(&[("apikey", &self.api_key)])
+            .header("User-Agent", "multi-tool-agent/1.0")
+            .send()
+            .await
+            .context("Failed to fetch GNews data")?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read GNews response")?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<GNewsError>(&body)
+                .map(|error| error.errors.to_string())
+                .unwrap_or(body);
+            let hint = if matches!(status.as_u16(), 401 | 403) { " Check the GNEWS_API_KEY environment variable." } else { "" };
+            anyhow::bail!("GNews error ({}): {}{}", status, message, hint);
+        }
+
+        let response: GNewsResponse = serde_json::from_str(&body).context("Failed to parse GNews response")?;
+        tracing::debug!("GNews returned {} of {} results", response.articles.len(), response.total_articles);
+        Ok(response.articles.into_iter().map(Article::from).collect())
+    }
+}
+
+#[async_trait]
+impl NewsProvider for GNewsProvider {
+    fn name(&self) -> &str {
+        "GNews"
+    }
+
+    async fn fetch(&self, location_info: &LocationInfo, request: &NewsRequest) -> Result<Vec<ScopedArticle>> {
+        for query in plan_queries(location_info, request.topic.as_deref(), GNEWS_COUNTRIES) {
+            let articles = self.fetch_articles(&query, request).await?;
+            if !articles.is_empty() {
+                return Ok(articles.into_iter().map(|article| ScopedArticle { scope: query.scope, article }).collect());
+            }
+            tracing::debug!("No GNews {} results for {}, widening", query.scope, query.label);
+        }
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::news::NewsScope;
+    use crate::test_support::{StubServer, tokyo};
+
+    const EMPTY: &str = r#"{"totalArticles": 0, "articles": []}"#;
+
+    #[tokio::test]
+    async fn searches_then_widens_to_headlines() {
+        let server = StubServer::start(vec![
+            (200, EMPTY),
+            (200, r#"{"totalArticles": 1, "articles": [
+                {"title": "Tokyo Giants win", "description": "", "url": "https://sports.example.jp/giants",
+                 "publishedAt": "2025-06-15T09:00:00Z", "source": {"name": "Sports Nippon", "url": "https://sports.example.jp"}}
+            ]}"#),
+        ])
+        .await;
+        let provider = GNewsProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest { topic: Some("sports".to_string()), count: 3, ..NewsRequest::default() };
+
+        let articles = provider.fetch(&tokyo(), &request).await.unwrap();
+
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].scope, NewsScope::National);
+        assert_eq!(articles[0].article.source, "Sports Nippon");
+        assert_eq!(articles[0].article.description, None);
+        let paths: Vec<String> = server.requests().into_iter().map(|request| request.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/search?q=%22Tokyo%22%20AND%20sports&sortby=publishedAt&max=3&country=jp&apikey=secret",
+                "/top-headlines?category=sports&max=3&country=jp&apikey=secret",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_language_and_dates() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = GNewsProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest {
+            topic: Some("strikes".to_string()),
+            from: NaiveDate::from_ymd_opt(2025, 6, 9),
+            to: NaiveDate::from_ymd_opt(2025, 6, 15),
+            language: Some("ja".to_string()),
+            ..NewsRequest::default()
+        };
+
+        provider.fetch(&tokyo(), &request).await.unwrap();
+
+        assert_eq!(
+            server.requests()[0].path,
+            "/search?q=%22Tokyo%22%20AND%20strikes&sortby=publishedAt&max=5&country=jp&lang=ja\
+            &from=2025-06-09T00%3A00%3A00Z&to=2025-06-16T00%3A00%3A00Z&apikey=secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn explains_key_errors() {
+        let server = StubServer::start(vec![(403, r#"{"errors": ["You did not provide a valid API key."]}"#)]).await;
+        let provider = GNewsProvider::new("wrong").with_base_url(&server.url);
+
+        let error = provider.fetch(&tokyo(), &NewsRequest::default()).await.unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "GNews error (403 Forbidden): [\"You did not provide a valid API key.\"] Check the GNEWS_API_KEY environment variable."
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use crate::LocationInfo;
+use super::{NewsRequest, NewsScope, ScopedArticle};
+
+/// Headlines sharing at least this fraction of their words are the same story.
+const TITLE_SIMILARITY: f64 = 0.8;
+
+/// Query parameters that only track where a click came from.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "ocid", "cmpid", "ref", "src"];
+
+/// Combines the providers' articles into one list of at most
+/// `request.count`, dropping any published outside the requested dates.
+///
+/// Articles that mention the place by name count as local, and ones that
+/// mention its region as regional, whatever query found them. The list is
+/// ranked narrowest scope first, then preferred sources, then newest first;
+/// of several copies of a story, the best-ranked one is kept.
+pub fn merge(location_info: &LocationInfo, results: Vec<Vec<ScopedArticle>>, request: &NewsRequest) -> Vec<ScopedArticle> {
+    let mut articles: Vec<ScopedArticle> = results
+        .into_iter()
+        .flatten()
+        .filter(|scoped| request.in_window(scoped.article.published_at))
+        .map(|mut scoped| {
+            scoped.scope = scoped.scope.min(relevance(location_info, &scoped));
+            scoped
+        })
+        .collect();
+    // Undated articles sort last within their scope.
+    articles.sort_by_key(|scoped| {
+        (scoped.scope, !request.prefers(&scoped.article), Reverse(scoped.article.published_at))
+    });
+
+    let mut kept: Vec<(String, HashSet<String>, ScopedArticle)> = Vec::new();
+    for scoped in articles {
+        let url = canonical_url(&scoped.article.url);
+        let words = title_words(&scoped.article.title);
+        match kept.iter_mut().find(|(kept_url, kept_words, _)| *kept_url == url || similarity(kept_words, &words) >= TITLE_SIMILARITY) {
+            Some((_, _, original)) => {
+                if original.article.description.is_none() {
+                    original.article.description = scoped.article.description;
+                }
+            }
+            None => kept.push((url, words, scoped)),
+        }
+    }
+
+    kept.into_iter().map(|(_, _, scoped)| scoped).take(request.count).collect()
+}
+
+fn relevance(location_info: &LocationInfo, scoped: &ScopedArticle) -> NewsScope {
+    if scoped.article.mentions(&location_info.name) {
+        NewsScope::Local
+    } else if location_info.admin1.as_deref().is_some_and(|region| scoped.article.mentions(region)) {
+        NewsScope::Regional
+    } else {
+        NewsScope::Global
+    }
+}
+
+/// The same page reached through different links: scheme, `www.`, trailing
+/// slashes, fragments and tracking parameters are ignored.
+fn canonical_url(url: &str) -> String {
+    let Ok(parsed) = reqwest::Url::parse(url.trim()) else {
+        return url.trim().to_lowercase();
+    };
+    let host = parsed.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let query: Vec<String> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+
+    let mut canonical = format!("{}{}", host, parsed.path().trim_end_matches('/'));
+    if !query.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&query.join("&"));
+    }
+    canonical
+}
+
+fn title_words(title: &str) -> HashSet<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Jaccard similarity of two headlines' word sets.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
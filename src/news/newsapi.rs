@@ -0,0 +1,430 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::{LocationInfo, NEWS_API};
+use super::{Article, NewsProvider, NewsQuery, NewsRequest, NewsScope, ScopedArticle, plan_queries};
+
+/// Countries NewsAPI's top-headlines `country` parameter accepts.
+const NEWS_API_COUNTRIES: &[&str] = &[
+    "ae", "ar", "at", "au", "be", "bg", "br", "ca", "ch", "cn", "co", "cu", "cz", "de", "eg", "fr", "gb", "gr",
+    "hk", "hu", "id", "ie", "il", "in", "it", "jp", "kr", "lt", "lv", "ma", "mx", "my", "ng", "nl", "no", "nz",
+    "ph", "pl", "pt", "ro", "rs", "ru", "sa", "se", "sg", "si", "sk", "th", "tr", "tw", "ua", "us", "ve", "za",
+];
+
+/// NewsAPI reports failures in the body as well as the status code, e.g.
+/// `{"status":"error","code":"apiKeyInvalid","message":"..."}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum NewsApiResponse {
+    #[serde(rename_all = "camelCase")]
+    Ok {
+        total_results: u64,
+        articles: Vec<NewsApiArticle>,
+    },
+    Error {
+        code: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewsApiArticle {
+    source: NewsApiSource,
+    author: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    url: String,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsApiSource {
+    name: Option<String>,
+}
+
+impl From<NewsApiArticle> for Article {
+    fn from(article: NewsApiArticle) -> Self {
+        let source = article.source.name.unwrap_or_default();
+        // NewsAPI titles usually end in " - <source>", which the rendering
+        // already shows.
+        let title = article.title.unwrap_or_default();
+        let title = title
+            .strip_suffix(&format!(" - {}", source))
+            .map(str::to_string)
+            .unwrap_or(title);
+        Self {
+            title,
+            source,
+            author: article.author.filter(|author| !author.is_empty()),
+            published_at: article.published_at,
+            url: article.url,
+            description: article.description.filter(|description| !description.is_empty()),
+        }
+    }
+}
+
+/// Headlines from newsapi.org, widening from the place to the world until a
+/// query returns anything.
+///
+/// Queries use `top-headlines`, which can be limited to a country's outlets,
+/// unless the request has dates, a language or source domains: only
+/// `everything` supports those, and it also reaches older articles. Such
+/// requests stay on `everything` while widening, with the country and topic
+/// as search terms, and stop before a global query with nothing to search.
+/// Requested sources that are not domains are passed to `top-headlines` as
+/// NewsAPI source ids, which replace the country and category filters.
+pub struct NewsApiProvider {
+    api_key: String,
+    base_url: String,
+}
+
+impl NewsApiProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self { api_key: api_key.into(), base_url: NEWS_API.to_string() }
+    }
+
+    /// Overrides the NewsAPI root (`.../v2`) under which `/everything` and
+    /// `/top-headlines` are requested.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The request URL for one query; see the type docs for the endpoint
+    /// choice. `None` when the query cannot be searched for.
+    fn url(&self, query: &NewsQuery, request: &NewsRequest) -> Option<String> {
+        let (domains, source_ids) = split_sources(&request.sources);
+        let search_everything =
+            request.from.is_some() || request.to.is_some() || request.language.is_some() || !domains.is_empty();
+        let (endpoint, params) = if search_everything {
+            ("everything", everything_params(&everything_terms(query)?, request, &domains))
+        } else {
+            ("top-headlines", top_headlines_params(query, &source_ids))
+        };
+        Some(format!(
+            "{base}/{endpoint}?pageSize={count}{params}",
+            base = self.base_url,
+            endpoint = endpoint,
+            count = request.count,
+            params = params
+        ))
+    }
+
+    /// Runs one NewsAPI query, turning `status: "error"` payloads into
+    /// errors. A query with nothing to search for returns no articles.
+    pub async fn fetch_articles(&self, query: &NewsQuery, request: &NewsRequest) -> Result<Vec<Article>> {
+        let Some(url) = self.url(query, request) else {
+            return Ok(Vec::new());
+        };
+        tracing::debug!("Trying NewsAPI {} query: {}", query.scope, url);
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("User-Agent", "multi-tool-agent/1.0")
+            .header("X-Api-Key", &self.api_key)
+            .send()
+            .await
+            .context("Failed to fetch news data")?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read news response")?;
+
+        match serde_json::from_str(&body) {
+            Ok(NewsApiResponse::Ok { total_results, articles }) => {
+                tracing::debug!("NewsAPI returned {} of {} results", articles.len(), total_results);
+                // Articles taken down by the publisher come back as "[Removed]" stubs.
+                Ok(articles
+                    .into_iter()
+                    .map(Article::from)
+                    .filter(|article| !article.title.is_empty() && article.title != "[Removed]")
+                    .collect())
+            }
+            Ok(NewsApiResponse::Error { code, message }) => Err(news_api_error(&code, &message)),
+            Err(e) => Err(e).with_context(|| format!("Failed to parse news response ({})", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl NewsProvider for NewsApiProvider {
+    fn name(&self) -> &str {
+        "NewsAPI"
+    }
+
+    async fn fetch(&self, location_info: &LocationInfo, request: &NewsRequest) -> Result<Vec<ScopedArticle>> {
+        let mut previous_url = None;
+        for query in plan_queries(location_info, request.topic.as_deref(), NEWS_API_COUNTRIES) {
+            let Some(url) = self.url(&query, request) else {
+                tracing::debug!("Nothing to search NewsAPI for at {} scope, stopping", query.scope);
+                break;
+            };
+            // Source ids replace the country and category, and `everything`
+            // has no country filter, so wider queries can repeat the last one.
+            if previous_url.as_ref() == Some(&url) {
+                continue;
+            }
+            previous_url = Some(url);
+            let articles = self.fetch_articles(&query, request).await?;
+            if !articles.is_empty() {
+                return Ok(articles.into_iter().map(|article| ScopedArticle { scope: query.scope, article }).collect());
+            }
+            tracing::debug!("No NewsAPI {} results for {}, widening", query.scope, query.label);
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Splits the requested sources into domains (`reuters.com`) and source ids,
+/// derived from names the way NewsAPI forms them (`BBC News` -> `bbc-news`).
+fn split_sources(sources: &[String]) -> (Vec<String>, Vec<String>) {
+    let sources = sources.iter().map(|source| source.trim().to_lowercase()).filter(|source| !source.is_empty());
+    let (domains, names): (Vec<String>, Vec<String>) = sources.partition(|source| source.contains('.'));
+    let ids = names.iter().map(|name| name.split_whitespace().collect::<Vec<_>>().join("-")).collect();
+    (domains, ids)
+}
+
+/// NewsAPI top-headlines parameters (with leading `&`). Source ids cannot be
+/// combined with a country or category.
+fn top_headlines_params(query: &NewsQuery, source_ids: &[String]) -> String {
+    let mut params = String::new();
+    if let Some(q) = &query.q {
+        params.push_str(&format!("&q={}", urlencoding::encode(q)));
+    }
+    if !source_ids.is_empty() {
+        params.push_str(&format!("&sources={}", urlencoding::encode(&source_ids.join(","))));
+        return params;
+    }
+    if let Some(country) = &query.country {
+        params.push_str(&format!("&country={}", country));
+    }
+    match &query.category {
+        Some(category) => params.push_str(&format!("&category={}", category)),
+        // top-headlines needs at least one filter; "general" is the
+        // broadest category.
+        None if query.q.is_none() && query.country.is_none() => params.push_str("&category=general"),
+        None => {}
+    }
+    params
+}
+
+/// Search terms for `everything`, which has no country or category filter:
+/// the place being asked about is added as a phrase and the category as a
+/// word. `None` for a global query without a topic.
+fn everything_terms(query: &NewsQuery) -> Option<String> {
+    let place = format!("\"{}\"", query.label);
+    let mut terms = Vec::new();
+    if query.scope != NewsScope::Global && !query.q.as_deref().is_some_and(|q| q.contains(&place)) {
+        terms.push(place);
+    }
+    terms.extend(query.q.clone());
+    terms.extend(query.category.clone());
+    (!terms.is_empty()).then(|| terms.join(" AND "))
+}
+
+/// NewsAPI everything parameters (with leading `&`). The endpoint has no
+/// country filter, so the place in `q` does all the narrowing.
+fn everything_params(q: &str, request: &NewsRequest, domains: &[String]) -> String {
+    let mut params = format!("&sortBy=publishedAt&q={}", urlencoding::encode(q));
+    if !domains.is_empty() {
+        params.push_str(&format!("&domains={}", urlencoding::encode(&domains.join(","))));
+    }
+    if let Some(from) = request.from {
+        params.push_str(&format!("&from={}", from));
+    }
+    if let Some(to) = request.to {
+        params.push_str(&format!("&to={}", to));
+    }
+    if let Some(language) = &request.language {
+        params.push_str(&format!("&language={}", language));
+    }
+    params
+}
+
+fn news_api_error(code: &str, message: &str) -> anyhow::Error {
+    let hint = match code {
+        "apiKeyMissing" | "apiKeyInvalid" | "apiKeyDisabled" | "apiKeyExhausted" => {
+            " Check the NEWS_API_KEY environment variable."
+        }
+        "rateLimited" => " The request limit for this key has been reached; try again later.",
+        _ => "",
+    };
+    anyhow::anyhow!("NewsAPI error {}: {}{}", code, message, hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::test_support::{StubServer, tokyo};
+
+    const EMPTY: &str = r#"{"status": "ok", "totalResults": 0, "articles": []}"#;
+
+    #[tokio::test]
+    async fn widens_until_a_query_returns_articles() {
+        let server = StubServer::start(vec![
+            (200, EMPTY),
+            (200, r#"{"status": "ok", "totalResults": 2, "articles": [
+                {"source": {"id": null, "name": "[Removed]"}, "title": "[Removed]", "url": "https://removed.com"},
+                {"source": {"id": "nhk", "name": "NHK"}, "author": "", "title": "Typhoon nears Kyushu - NHK",
+                 "description": "Heavy rain expected.", "url": "https://www3.nhk.or.jp/news/1",
+                 "publishedAt": "2025-06-15T03:00:00Z"}
+            ]}"#),
+        ])
+        .await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+
+        let articles = provider.fetch(&tokyo(), &NewsRequest::default()).await.unwrap();
+
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].scope, NewsScope::National);
+        assert_eq!(articles[0].article.title, "Typhoon nears Kyushu");
+        assert_eq!(articles[0].article.source, "NHK");
+        assert_eq!(articles[0].article.author, None);
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/top-headlines?pageSize=5&q=%22Tokyo%22&country=jp");
+        assert_eq!(requests[1].path, "/top-headlines?pageSize=5&country=jp");
+        assert_eq!(requests[0].header("x-api-key"), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn searches_everything_for_dated_topics() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest {
+            topic: Some("strikes".to_string()),
+            from: NaiveDate::from_ymd_opt(2025, 6, 9),
+            language: Some("en".to_string()),
+            ..NewsRequest::default()
+        };
+
+        let articles = provider.fetch(&tokyo(), &request).await.unwrap();
+
+        assert!(articles.is_empty());
+        let paths: Vec<String> = server.requests().into_iter().map(|request| request.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Tokyo%22%20AND%20strikes&from=2025-06-09&language=en",
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Japan%22%20AND%20strikes&from=2025-06-09&language=en",
+                "/everything?pageSize=5&sortBy=publishedAt&q=strikes&from=2025-06-09&language=en",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dated_requests_stay_on_everything_while_widening() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest { to: NaiveDate::from_ymd_opt(2025, 6, 15), ..NewsRequest::default() };
+
+        provider.fetch(&tokyo(), &request).await.unwrap();
+
+        // Global headlines cannot be limited to the dates, so widening stops
+        // at the country.
+        let paths: Vec<String> = server.requests().into_iter().map(|request| request.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Tokyo%22&to=2025-06-15",
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Japan%22&to=2025-06-15",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dated_category_topics_are_searched_as_words() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest {
+            topic: Some("sports".to_string()),
+            from: NaiveDate::from_ymd_opt(2025, 6, 9),
+            ..NewsRequest::default()
+        };
+
+        provider.fetch(&tokyo(), &request).await.unwrap();
+
+        let paths: Vec<String> = server.requests().into_iter().map(|request| request.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Tokyo%22%20AND%20sports&from=2025-06-09",
+                "/everything?pageSize=5&sortBy=publishedAt&q=%22Japan%22%20AND%20sports&from=2025-06-09",
+                "/everything?pageSize=5&sortBy=publishedAt&q=sports&from=2025-06-09",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_searches_to_requested_domains() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest {
+            topic: Some("strikes".to_string()),
+            sources: vec!["Reuters.com".to_string(), " apnews.com ".to_string()],
+            ..NewsRequest::default()
+        };
+
+        provider.fetch(&tokyo(), &request).await.unwrap();
+
+        assert_eq!(
+            server.requests()[0].path,
+            "/everything?pageSize=5&sortBy=publishedAt&q=%22Tokyo%22%20AND%20strikes&domains=reuters.com%2Capnews.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn asks_headlines_from_requested_source_ids() {
+        let server = StubServer::start(vec![(200, EMPTY)]).await;
+        let provider = NewsApiProvider::new("secret").with_base_url(&server.url);
+        let request = NewsRequest {
+            topic: Some("sports".to_string()),
+            sources: vec!["BBC Sport".to_string(), "espn".to_string()],
+            ..NewsRequest::default()
+        };
+
+        provider.fetch(&tokyo(), &request).await.unwrap();
+
+        let paths: Vec<String> = server.requests().into_iter().map(|request| request.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/top-headlines?pageSize=5&q=%22Tokyo%22%20AND%20sports&sources=bbc-sport%2Cespn",
+                "/top-headlines?pageSize=5&sources=bbc-sport%2Cespn",
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_sources_into_domains_and_ids() {
+        let sources = ["BBC News", "reuters.com", "", "Al  Jazeera English"].map(str::to_string);
+
+        let (domains, ids) = split_sources(&sources);
+
+        assert_eq!(domains, ["reuters.com"]);
+        assert_eq!(ids, ["bbc-news", "al-jazeera-english"]);
+    }
+
+    #[tokio::test]
+    async fn explains_key_errors() {
+        let server = StubServer::start(vec![(
+            401,
+            r#"{"status": "error", "code": "apiKeyInvalid", "message": "Your API key is invalid."}"#,
+        )])
+        .await;
+        let provider = NewsApiProvider::new("wrong").with_base_url(&server.url);
+
+        let error = provider.fetch(&tokyo(), &NewsRequest::default()).await.unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "NewsAPI error apiKeyInvalid: Your API key is invalid. Check the NEWS_API_KEY environment variable."
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+}
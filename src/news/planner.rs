@@ -0,0 +1,235 @@
+use std::fmt;
+use crate::LocationInfo;
+
+/// How far a query is from the location asked about, narrowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NewsScope {
+    Local,
+    Regional,
+    National,
+    Global,
+}
+
+impl fmt::Display for NewsScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Local => "local",
+            Self::Regional => "regional",
+            Self::National => "national",
+            Self::Global => "global",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Topics both NewsAPI and GNews offer as headline categories.
+const CATEGORIES: &[&str] = &["business", "entertainment", "health", "science", "sports", "technology"];
+
+/// One headlines request in the fallback chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsQuery {
+    pub scope: NewsScope,
+    /// Search terms: places quoted as phrases, joined to topic words with
+    /// `AND`.
+    pub q: Option<String>,
+    /// Headline category, used instead of search terms for national and
+    /// global queries about a category topic such as "sports".
+    pub category: Option<String>,
+    /// Lowercase ISO country code for the provider's country filter.
+    pub country: Option<String>,
+    /// Human-readable target, e.g. "Tokyo" or "Japan".
+    pub label: String,
+}
+
+/// Builds the queries to try in order: the place itself, its region, the
+/// country's headlines, then global headlines, each narrowed to `topic`
+/// when one is given.
+///
+/// Local and regional searches are limited to the country's outlets when
+/// the provider covers that country (`supported_countries`, lowercase ISO
+/// codes), which keeps same-named places abroad out.
+pub fn plan_queries(location_info: &LocationInfo, topic: Option<&str>, supported_countries: &[&str]) -> Vec<NewsQuery> {
+    let country = location_info.country_code
+        .as_deref()
+        .map(str::to_lowercase)
+        .filter(|code| supported_countries.contains(&code.as_str()));
+    let topic = topic.map(|topic| topic.trim().to_lowercase()).filter(|topic| !topic.is_empty());
+    let category = topic.clone().filter(|topic| CATEGORIES.contains(&topic.as_str()));
+    // Category topics are matched by category at the wider scopes; any
+    // other topic stays in the search terms.
+    let keywords = topic.as_deref().filter(|_| category.is_none());
+
+    let search = |place: Option<&str>, topic: Option<&str>| -> Option<String> {
+        let terms: Vec<String> = place
+            .map(|place| format!("\"{}\"", place))
+            .into_iter()
+            .chain(topic.into_iter().flat_map(str::split_whitespace).map(str::to_string))
+            .collect();
+        (!terms.is_empty()).then(|| terms.join(" AND "))
+    };
+
+    let mut queries = vec![NewsQuery {
+        scope: NewsScope::Local,
+        q: search(Some(&location_info.name), topic.as_deref()),
+        category: None,
+        country: country.clone(),
+        label: location_info.name.clone(),
+    }];
+
+    if let Some(region) = location_info.admin1.as_deref().filter(|region| *region != location_info.name) {
+        queries.push(NewsQuery {
+            scope: NewsScope::Regional,
+            q: search(Some(region), topic.as_deref()),
+            category: None,
+            country: country.clone(),
+            label: region.to_string(),
+        });
+    }
+
+    if let Some(country_name) = location_info.country.as_ref().or(location_info.country_code.as_ref()) {
+        // Without provider coverage, search for the country by name instead.
+        let place = country.is_none().then_some(country_name.as_str());
+        queries.push(NewsQuery {
+            scope: NewsScope::National,
+            q: search(place, keywords),
+            category: category.clone(),
+            country,
+            label: country_name.clone(),
+        });
+    }
+
+    queries.push(NewsQuery {
+        scope: NewsScope::Global,
+        q: search(None, keywords),
+        category,
+        country: None,
+        label: "worldwide".to_string(),
+    });
+    queries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::location;
+
+    const SUPPORTED: &[&str] = &["jp", "us"];
+
+    fn place(name: &str, admin1: Option<&str>, country: Option<&str>, country_code: Option<&str>) -> LocationInfo {
+        let mut location_info = location(name, 0.0, 0.0);
+        location_info.admin1 = admin1.map(str::to_string);
+        location_info.country = country.map(str::to_string);
+        location_info.country_code = country_code.map(str::to_string);
+        location_info
+    }
+
+    /// `(scope, q, category, country)` of a query, for compact comparisons.
+    type Summary<'a> = (NewsScope, Option<&'a str>, Option<&'a str>, Option<&'a str>);
+
+    fn summary(queries: &[NewsQuery]) -> Vec<Summary<'_>> {
+        queries
+            .iter()
+            .map(|query| (query.scope, query.q.as_deref(), query.category.as_deref(), query.country.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn widens_from_the_place_to_the_world() {
+        use NewsScope::*;
+        let cases: [(LocationInfo, Option<&str>, Vec<Summary>); 4] = [
+            (
+                place("Osaka", Some("Osaka Prefecture"), Some("Japan"), Some("JP")),
+                None,
+                vec![
+                    (Local, Some("\"Osaka\""), None, Some("jp")),
+                    (Regional, Some("\"Osaka Prefecture\""), None, Some("jp")),
+                    (National, None, None, Some("jp")),
+                    (Global, None, None, None),
+                ],
+            ),
+            (
+                place("Osaka", Some("Osaka Prefecture"), Some("Japan"), Some("JP")),
+                Some("Sports"),
+                vec![
+                    (Local, Some("\"Osaka\" AND sports"), None, Some("jp")),
+                    (Regional, Some("\"Osaka Prefecture\" AND sports"), None, Some("jp")),
+                    (National, None, Some("sports"), Some("jp")),
+                    (Global, None, Some("sports"), None),
+                ],
+            ),
+            (
+                place("Denver", Some("Colorado"), Some("United States"), Some("US")),
+                Some("transport strikes"),
+                vec![
+                    (Local, Some("\"Denver\" AND transport AND strikes"), None, Some("us")),
+                    (Regional, Some("\"Colorado\" AND transport AND strikes"), None, Some("us")),
+                    (National, Some("transport AND strikes"), None, Some("us")),
+                    (Global, Some("transport AND strikes"), None, None),
+                ],
+            ),
+            (
+                place("Atlantis", None, None, None),
+                Some("  "),
+                vec![(Local, Some("\"Atlantis\""), None, None), (Global, None, None, None)],
+            ),
+        ];
+
+        for (location_info, topic, expected) in cases {
+            let queries = plan_queries(&location_info, topic, SUPPORTED);
+
+            assert_eq!(summary(&queries), expected, "{} / {:?}", location_info.name, topic);
+        }
+    }
+
+    #[test]
+    fn skips_the_region_when_absent_or_same_as_the_place() {
+        let cases = [
+            place("Singapore", Some("Singapore"), Some("Singapore"), Some("SG")),
+            place("Monaco", None, Some("Monaco"), Some("MC")),
+        ];
+
+        for location_info in cases {
+            let queries = plan_queries(&location_info, None, SUPPORTED);
+
+            let scopes: Vec<NewsScope> = queries.iter().map(|query| query.scope).collect();
+            assert_eq!(scopes, [NewsScope::Local, NewsScope::National, NewsScope::Global], "{}", location_info.name);
+        }
+    }
+
+    #[test]
+    fn searches_unsupported_countries_by_name() {
+        let lyon = place("Lyon", Some("Auvergne-Rhône-Alpes"), Some("France"), Some("FR"));
+
+        let queries = plan_queries(&lyon, Some("strikes"), SUPPORTED);
+
+        assert!(queries.iter().all(|query| query.country.is_none()), "{:?}", queries);
+        assert_eq!(queries[2].q.as_deref(), Some("\"France\" AND strikes"));
+        assert_eq!(queries[2].label, "France");
+    }
+
+    #[test]
+    fn never_mentions_another_country() {
+        let locations = [
+            place("Tokyo", Some("Tokyo"), Some("Japan"), Some("JP")),
+            place("Lagos", Some("Lagos"), Some("Nigeria"), Some("NG")),
+            place("Denver", Some("Colorado"), None, Some("us")),
+        ];
+
+        for location_info in locations {
+            for query in plan_queries(&location_info, Some("politics"), SUPPORTED) {
+                let q = query.q.unwrap_or_default();
+                assert!(!q.contains("France"), "{}: {}", location_info.name, q);
+                assert_ne!(query.label, "France");
+            }
+        }
+    }
+
+    #[test]
+    fn labels_each_scope_with_its_place() {
+        let denver = place("Denver", Some("Colorado"), None, Some("US"));
+
+        let labels: Vec<String> = plan_queries(&denver, None, SUPPORTED).into_iter().map(|query| query.label).collect();
+
+        assert_eq!(labels, ["Denver", "Colorado", "US", "worldwide"]);
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::future;
+use serde::{Deserialize, Serialize};
+use crate::{LocationInfo, feeds::fetch_document};
+use super::{Article, NewsProvider, NewsRequest, NewsScope, ScopedArticle};
+
+/// An RSS 2.0 or Atom news feed from the `[[news.feeds]]` config entries.
+///
+/// A feed listing `cities` is treated as local news for those places, one
+/// listing `countries` (ISO codes) as national news; a feed with neither is
+/// global. `url` may be a local file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsFeed {
+    pub url: String,
+    /// Overrides the feed's own title as the article source.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub countries: Vec<String>,
+    #[serde(default)]
+    pub cities: Vec<String>,
+}
+
+impl NewsFeed {
+    fn scope_for(&self, location_info: &LocationInfo) -> Option<NewsScope> {
+        let matches_city = self.cities.iter().any(|city| city.eq_ignore_ascii_case(&location_info.name));
+        let matches_country = location_info.country_code
+            .as_deref()
+            .is_some_and(|code| self.countries.iter().any(|country| country.eq_ignore_ascii_case(code)));
+
+        if matches_city {
+            Some(NewsScope::Local)
+        } else if matches_country {
+            Some(NewsScope::National)
+        } else if self.cities.is_empty() && self.countries.is_empty() {
+            Some(NewsScope::Global)
+        } else {
+            None
+        }
+    }
+}
+
+/// Keyless news from configured feeds.
+pub struct RssProvider {
+    feeds: Vec<NewsFeed>,
+    max_age: Duration,
+}
+
+impl RssProvider {
+    pub fn new(feeds: Vec<NewsFeed>, max_age_hours: u64) -> Self {
+        Self { feeds, max_age: Duration::hours(max_age_hours as i64) }
+    }
+}
+
+#[async_trait]
+impl NewsProvider for RssProvider {
+    fn name(&self) -> &str {
+        "RSS"
+    }
+
+    /// Recent items on the requested topic from the feeds that cover the
+    /// location, each tagged with its feed's scope. Items from global feeds
+    /// are only kept when they mention the place, its region or its country.
+    /// A requested start date replaces the maximum age. A feed that is down
+    /// is skipped.
+    async fn fetch(&self, location_info: &LocationInfo, request: &NewsRequest) -> Result<Vec<ScopedArticle>> {
+        let cutoff = request.since().unwrap_or_else(|| Utc::now() - self.max_age);
+        let feeds: Vec<(&NewsFeed, NewsScope)> = self.feeds
+            .iter()
+            .filter_map(|feed| feed.scope_for(location_info).map(|scope| (feed, scope)))
+            .collect();
+        let results = future::join_all(feeds.iter().map(|(feed, _)| fetch_feed(feed))).await;
+
+        let mut articles = Vec::new();
+        for ((feed, scope), result) in feeds.iter().zip(results) {
+            match result {
+                Ok(feed_articles) => articles.extend(
+                    feed_articles
+                        .into_iter()
+                        .filter(|article| article.published_at.is_none_or(|published_at| published_at >= cutoff))
+                        .filter(|article| request.matches_topic(article))
+                        .filter(|article| *scope != NewsScope::Global || mentions_place(article, location_info))
+                        .map(|article| ScopedArticle { scope: *scope, article }),
+                ),
+                Err(e) => tracing::warn!("Skipping news feed {}: {:#}", feed.url, e),
+            }
+        }
+        Ok(articles)
+    }
+}
+
+fn mentions_place(article: &Article, location_info: &LocationInfo) -> bool {
+    std::iter::once(&location_info.name)
+        .chain(location_info.admin1.as_ref())
+        .chain(location_info.country.as_ref())
+        .any(|place| article.mentions(place))
+}
+
+async fn fetch_feed(feed: &NewsFeed) -> Result<Vec<Article>> {
+    let xml = fetch_document(&feed.url, "application/rss+xml, application/atom+xml, application/xml").await?;
+    parse_feed(&xml, feed.name.as_deref()).with_context(|| format!("Failed to parse news feed {}", feed.url))
+}
+
+/// Parses an RSS 2.0 channel or an Atom feed. The channel or feed title is
+/// the article source unless `source` is given.
+pub fn parse_feed(xml: &str, source: Option<&str>) -> Result<Vec<Article>> {
+    let document = roxmltree::Document::parse(xml).context("Invalid XML")?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "rss" => {
+            let channel = root
+                .children()
+                .find(|node| node.has_tag_name("channel"))
+                .context("RSS feed has no <channel>")?;
+            let source = source.map(str::to_string).or_else(|| child_text(channel, "title")).unwrap_or_default();
+            Ok(channel
+                .children()
+                .filter(|node| node.has_tag_name("item"))
+                .filter_map(|item| parse_rss_item(item, &source))
+                .collect())
+        }
+        "feed" => {
+            let source = source.map(str::to_string).or_else(|| child_text(root, "title")).unwrap_or_default();
+            Ok(root
+                .children()
+                .filter(|node| node.has_tag_name("entry"))
+                .filter_map(|entry| parse_atom_entry(entry, &source))
+                .collect())
+        }
+        other => anyhow::bail!("Expected an RSS or Atom feed, found <{}>", other),
+    }
+}
+
+fn parse_rss_item(item: roxmltree::Node, source: &str) -> Option<Article> {
+    Some(Article {
+        title: child_text(item, "title").map(|title| strip_html(&title))?,
+        source: source.to_string(),
+        // `dc:creator` is far more common than RSS's own e-mail `author`.
+        author: child_text(item, "creator").or_else(|| child_text(item, "author")),
+        published_at: child_text(item, "pubDate")
+            .and_then(|date| DateTime::parse_from_rfc2822(&date).ok())
+            .or_else(|| child_text(item, "date").and_then(|date| DateTime::parse_from_rfc3339(&date).ok()))
+            .map(|date| date.with_timezone(&Utc)),
+        url: child_text(item, "link").or_else(|| child_text(item, "guid"))?,
+        description: child_text(item, "description").map(|description| strip_html(&description)),
+    })
+}
+
+fn parse_atom_entry(entry: roxmltree::Node, source: &str) -> Option<Article> {
+    let links: Vec<roxmltree::Node> = entry.children().filter(|node| node.has_tag_name("link")).collect();
+    let url = links
+        .iter()
+        .find(|link| link.attribute("rel").is_none_or(|rel| rel == "alternate"))
+        .or_else(|| links.first())
+        .and_then(|link| link.attribute("href"))?;
+
+    Some(Article {
+        title: child_text(entry, "title").map(|title| strip_html(&title))?,
+        source: source.to_string(),
+        author: entry
+            .children()
+            .find(|node| node.has_tag_name("author"))
+            .and_then(|author| child_text(author, "name")),
+        published_at: child_text(entry, "published")
+            .or_else(|| child_text(entry, "updated"))
+            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+            .map(|date| date.with_timezone(&Utc)),
+        url: url.to_string(),
+        description: child_text(entry, "summary")
+            .or_else(|| child_text(entry, "content"))
+            .map(|description| strip_html(&description)),
+    })
+}
+
+/// Feed descriptions are often HTML; keep the text only.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::test_support::{fixture, location, tokyo};
+
+    /// Old enough that the fixtures never age out.
+    const FOREVER_HOURS: u64 = 24 * 365 * 100;
+
+    fn feed(name: &str) -> NewsFeed {
+        NewsFeed { url: fixture(name), name: None, countries: Vec::new(), cities: Vec::new() }
+    }
+
+    /// The RSS fixture as a city feed, so its items are not location-filtered.
+    fn tokyo_daily() -> NewsFeed {
+        NewsFeed { cities: vec!["Tokyo".to_string()], ..feed("news.rss") }
+    }
+
+    async fn fetch(feeds: Vec<NewsFeed>, max_age_hours: u64, request: &NewsRequest) -> Vec<ScopedArticle> {
+        RssProvider::new(feeds, max_age_hours).fetch(&tokyo(), request).await.unwrap()
+    }
+
+    fn titles(articles: &[ScopedArticle]) -> Vec<&str> {
+        articles.iter().map(|scoped| scoped.article.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn reads_rss_items() {
+        let articles = fetch(vec![tokyo_daily()], FOREVER_HOURS, &NewsRequest::default()).await;
+
+        assert_eq!(titles(&articles), ["Rail strike called off", "Cherry blossom season ends early", "Ward office opening hours"]);
+        let strike = &articles[0].article;
+        assert_eq!(strike.source, "Tokyo Daily");
+        assert_eq!(strike.author.as_deref(), Some("Aiko Tanaka"));
+        assert_eq!(strike.url, "https://tokyo-daily.example.com/rail-strike");
+        assert_eq!(
+            strike.description.as_deref(),
+            Some("Unions and JR East reached a deal late on Saturday. Trains run as normal & on time.")
+        );
+        assert_eq!(strike.published_at, Some("2025-06-15T00:30:00Z".parse().unwrap()));
+        assert_eq!(articles[1].article.url, "https://tokyo-daily.example.com/sakura");
+        assert_eq!(articles[2].article.published_at, None);
+    }
+
+    #[tokio::test]
+    async fn reads_atom_entries() {
+        let wire = NewsFeed { name: Some("Wire".to_string()), countries: vec!["JP".to_string()], ..feed("news.atom") };
+
+        let articles = fetch(vec![wire], FOREVER_HOURS, &NewsRequest::default()).await;
+
+        assert_eq!(titles(&articles), ["Port strike spreads", "Markets close higher", "Record June heat in Tokyo"]);
+        let strike = &articles[0].article;
+        assert_eq!(strike.source, "Wire");
+        assert_eq!(strike.url, "https://world-wire.example.com/port-strike");
+        assert_eq!(strike.author.as_deref(), Some("Sam Okafor"));
+        assert_eq!(strike.description.as_deref(), Some("Dock workers in Japan and two other countries joined."));
+        assert_eq!(strike.published_at, Some("2025-06-15T10:00:00Z".parse().unwrap()));
+        assert_eq!(articles[1].article.url, "https://world-wire.example.com/markets");
+        assert_eq!(articles[1].article.description.as_deref(), Some("Stocks rose for a third day."));
+    }
+
+    #[tokio::test]
+    async fn drops_items_older_than_the_max_age() {
+        let articles = fetch(vec![tokyo_daily()], 24, &NewsRequest::default()).await;
+
+        assert_eq!(titles(&articles), ["Ward office opening hours"]);
+    }
+
+    #[tokio::test]
+    async fn a_start_date_replaces_the_max_age() {
+        let request = NewsRequest { from: NaiveDate::from_ymd_opt(2025, 6, 14), ..NewsRequest::default() };
+
+        let articles = fetch(vec![tokyo_daily(), feed("news.atom")], 24, &request).await;
+
+        assert_eq!(titles(&articles), ["Rail strike called off", "Ward office opening hours", "Port strike spreads"]);
+    }
+
+    #[tokio::test]
+    async fn filters_by_topic_and_skips_broken_feeds() {
+        let request = NewsRequest { topic: Some("strikes".to_string()), ..NewsRequest::default() };
+
+        let articles = fetch(vec![feed("missing.rss"), tokyo_daily(), feed("news.atom")], FOREVER_HOURS, &request).await;
+
+        assert_eq!(titles(&articles), ["Rail strike called off", "Port strike spreads"]);
+    }
+
+    #[tokio::test]
+    async fn global_feeds_keep_items_mentioning_the_place_or_country() {
+        let mut paris = location("Paris", 48.86, 2.35);
+        paris.admin1 = Some("Île-de-France".to_string());
+        paris.country = Some("France".to_string());
+        paris.country_code = Some("FR".to_string());
+        let provider = RssProvider::new(vec![feed("news.atom")], FOREVER_HOURS);
+
+        let tokyo_articles = provider.fetch(&tokyo(), &NewsRequest::default()).await.unwrap();
+        let paris_articles = provider.fetch(&paris, &NewsRequest::default()).await.unwrap();
+
+        assert_eq!(titles(&tokyo_articles), ["Port strike spreads", "Record June heat in Tokyo"]);
+        assert!(tokyo_articles.iter().all(|scoped| scoped.scope == NewsScope::Global));
+        assert!(paris_articles.is_empty(), "{:?}", paris_articles);
+    }
+
+    #[test]
+    fn scopes_feeds_by_city_then_country() {
+        let city = NewsFeed { cities: vec!["tokyo".to_string()], countries: vec!["JP".to_string()], ..feed("news.rss") };
+        let country = NewsFeed { countries: vec!["jp".to_string()], ..feed("news.rss") };
+        let abroad = NewsFeed { countries: vec!["FR".to_string()], ..feed("news.rss") };
+
+        assert_eq!(city.scope_for(&tokyo()), Some(NewsScope::Local));
+        assert_eq!(country.scope_for(&tokyo()), Some(NewsScope::National));
+        assert_eq!(feed("news.rss").scope_for(&tokyo()), Some(NewsScope::Global));
+        assert_eq!(abroad.scope_for(&tokyo()), None);
+    }
+
+    #[tokio::test]
+    async fn tags_articles_with_their_feed_scope() {
+        let national = NewsFeed { countries: vec!["JP".to_string()], ..feed("news.rss") };
+        let abroad = NewsFeed { countries: vec!["FR".to_string()], ..feed("news.atom") };
+
+        let articles = fetch(vec![national, abroad], FOREVER_HOURS, &NewsRequest::default()).await;
+
+        assert_eq!(articles.len(), 3);
+        assert!(articles.iter().all(|scoped| scoped.scope == NewsScope::National), "{:?}", articles);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let error = parse_feed("<html><body/></html>", None).unwrap_err();
+
+        assert_eq!(error.to_string(), "Expected an RSS or Atom feed, found <html>");
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use crate::{alerts::Severity, watch::{AlertChange, ChangeKind}};
+
+pub mod command;
+pub mod email;
+pub mod webhook;
+
+pub use command::CommandSink;
+pub use email::{EmailConfig, EmailSink, SmtpSecurity};
+pub use webhook::{SlackSink, WebhookSink};
+
+/// A message for the user: a batch of alert changes or a free-form briefing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub location: String,
+    pub title: String,
+    pub body: String,
+    /// Highest severity among the alerts still in effect, if any.
+    pub severity: Option<Severity>,
+    /// Alert changes behind this notification; empty for briefings.
+    pub changes: Vec<AlertChange>,
+}
+
+impl Notification {
+    pub fn new(location: impl Into<String>, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            title: title.into(),
+            body: body.into(),
+            severity: None,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Summarizes the changes for one location, one line per change.
+    pub fn from_changes(location: &str, changes: Vec<AlertChange>) -> Self {
+        let count = |wanted: fn(&ChangeKind) -> bool| changes.iter().filter(|change| wanted(&change.change)).count();
+        let counts: Vec<String> = [
+            (count(|change| *change == ChangeKind::New), "new"),
+            (count(|change| matches!(change, ChangeKind::Escalated { .. })), "escalated"),
+            (count(|change| *change == ChangeKind::Cleared), "cleared"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+
+        Self {
+            location: location.to_string(),
+            title: format!("Weather alerts for {}: {}", location, counts.join(", ")),
+            body: changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"),
+            severity: changes
+                .iter()
+                .filter(|change| change.change != ChangeKind::Cleared)
+                .map(|change| change.alert.severity)
+                .max(),
+            changes,
+        }
+    }
+}
+
+/// Somewhere notifications can be delivered.
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// One named entry of the `[sinks]` config section.
+///
+/// ```toml
+/// [sinks.team]
+/// type = "slack"
+/// url = "https://hooks.slack.com/services/..."
+///
+/// [sinks.pager]
+/// type = "command"
+/// command = "/usr/local/bin/page-oncall"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    /// POSTs the notification as JSON.
+    Webhook { url: String },
+    /// Slack-compatible incoming webhook.
+    Slack { url: String },
+    Email(EmailConfig),
+    /// Runs a program with the notification JSON on stdin.
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl SinkConfig {
+    pub fn build(&self, name: &str) -> Result<Arc<dyn NotificationSink>> {
+        Ok(match self {
+            Self::Webhook { url } => Arc::new(WebhookSink::new(name, url)),
+            Self::Slack { url } => Arc::new(SlackSink::new(name, url)),
+            Self::Email(config) => Arc::new(EmailSink::new(name, config.clone())?),
+            Self::Command { command, args } => Arc::new(CommandSink::new(name, command, args.clone())),
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use super::{Notification, NotificationSink};
+
+/// Runs a local program per notification. The notification is written to
+/// stdin as JSON; title and location are also passed as `NOTIFY_TITLE` and
+/// `NOTIFY_LOCATION` for simple shell scripts.
+pub struct CommandSink {
+    name: String,
+    command: String,
+    args: Vec<String>,
+}
+
+impl CommandSink {
+    pub fn new(name: impl Into<String>, command: impl Into<String>, args: Vec<String>) -> Self {
+        Self { name: name.into(), command: command.into(), args }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for CommandSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let payload = serde_json::to_vec(notification).context("Failed to serialize notification")?;
+
+        let mut child = tokio::process::Command::new(&self.command)
+            .args(&self.args)
+            .env("NOTIFY_TITLE", &notification.title)
+            .env("NOTIFY_LOCATION", &notification.location)
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", self.command))?;
+
+        // Commands may exit without reading stdin; only the exit status
+        // decides whether the notification was delivered.
+        if let Some(mut stdin) = child.stdin.take() {
+            match stdin.write_all(&payload).await {
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    tracing::debug!("Command sink {} did not read the notification", self.name);
+                }
+                result => result.context("Failed to write notification to command")?,
+            }
+        }
+
+        let status = child.wait().await.with_context(|| format!("Failed to wait for {}", self.command))?;
+        if !status.success() {
+            anyhow::bail!("Command sink {} exited with {}", self.name, status);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> CommandSink {
+        CommandSink::new("script", "sh", vec!["-c".to_string(), script.to_string()])
+    }
+
+    #[tokio::test]
+    async fn writes_the_notification_to_stdin_with_the_title_in_the_environment() {
+        let directory = std::env::temp_dir().join(format!("command-sink-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let payload = directory.join("payload.json");
+        let title = directory.join("title.txt");
+        let sink = shell(&format!(
+            "cat > '{}' && printf '%s' \"$NOTIFY_TITLE\" > '{}'",
+            payload.display(),
+            title.display()
+        ));
+        let notification = Notification::new("Tokyo", "Weather alerts for Tokyo: 1 new", "Tokyo: new heat alert");
+
+        sink.send(&notification).await.unwrap();
+
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&payload).unwrap()).unwrap();
+        assert_eq!(written["location"], "Tokyo");
+        assert_eq!(written["body"], "Tokyo: new heat alert");
+        assert_eq!(std::fs::read_to_string(&title).unwrap(), "Weather alerts for Tokyo: 1 new");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn non_zero_exit_is_an_error() {
+        let sink = shell("cat > /dev/null; exit 3");
+
+        let error = sink.send(&Notification::new("Tokyo", "Title", "Body")).await.unwrap_err();
+
+        assert!(error.to_string().contains("Command sink script exited with"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn commands_may_ignore_stdin() {
+        // Larger than a pipe buffer, so the write hits the closed pipe.
+        let body = "x".repeat(1 << 20);
+
+        let sent = shell("exit 0").send(&Notification::new("Tokyo", "Title", &body)).await;
+        let error = shell("exit 3").send(&Notification::new("Tokyo", "Title", &body)).await.unwrap_err();
+
+        assert!(sent.is_ok(), "{:?}", sent);
+        assert!(error.to_string().contains("Command sink script exited with"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn missing_programs_are_reported() {
+        let sink = CommandSink::new("script", "/nonexistent/notify", Vec::new());
+
+        let error = sink.send(&Notification::new("Tokyo", "Title", "Body")).await.unwrap_err();
+
+        assert!(error.to_string().contains("Failed to start /nonexistent/notify"), "{}", error);
+    }
+}
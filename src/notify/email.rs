@@ -0,0 +1,204 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use serde::{Deserialize, Serialize};
+use super::{Notification, NotificationSink};
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain SMTP, for local relays and test servers.
+    None,
+    #[default]
+    StartTls,
+    /// Implicit TLS, usually port 465.
+    Tls,
+}
+
+/// SMTP settings of an `email` sink. Credentials are read from the named
+/// environment variables so they stay out of the config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    /// Defaults to the usual port for `security`.
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub username_env: Option<String>,
+    #[serde(default)]
+    pub password_env: Option<String>,
+}
+
+/// Sends each notification as a plain-text email.
+pub struct EmailSink {
+    name: String,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailSink {
+    pub fn new(name: impl Into<String>, config: EmailConfig) -> Result<Self> {
+        let name = name.into();
+        let from = config.from.parse().with_context(|| format!("Invalid from address '{}'", config.from))?;
+        let to = config.to
+            .iter()
+            .map(|address| address.parse().with_context(|| format!("Invalid to address '{}'", address)))
+            .collect::<Result<Vec<Mailbox>>>()?;
+        if to.is_empty() {
+            anyhow::bail!("Email sink {} has no recipients", name);
+        }
+
+        let mut builder = match config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host).port(config.port.unwrap_or(25)),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .context("Failed to set up STARTTLS")?
+                .port(config.port.unwrap_or(587)),
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .context("Failed to set up TLS")?
+                .port(config.port.unwrap_or(465)),
+        };
+
+        if let (Some(username_env), Some(password_env)) = (&config.username_env, &config.password_env) {
+            let username = std::env::var(username_env).with_context(|| format!("{} is not set", username_env))?;
+            let password = std::env::var(password_env).with_context(|| format!("{} is not set", password_env))?;
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self { name, from, to, transport: builder.build() })
+    }
+}
+
+#[async_trait]
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in &self.to {
+            message = message.to(recipient.clone());
+        }
+        let message = message
+            .body(notification.body.clone())
+            .context("Failed to build email")?;
+
+        self.transport
+            .send(message)
+            .await
+            .with_context(|| format!("Email sink {} failed", self.name))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Accepts one SMTP session, answering `rcpt_reply` to `RCPT TO`, and
+    /// returns everything the client sent.
+    async fn smtp_server(rcpt_reply: &'static str) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let session = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut transcript = String::new();
+            writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                transcript.push_str(&line);
+                transcript.push('\n');
+                let command = line.to_uppercase();
+                let reply = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    "250 localhost"
+                } else if command.starts_with("RCPT") {
+                    rcpt_reply
+                } else if command == "DATA" {
+                    writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").await.unwrap();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if line == "." {
+                            break;
+                        }
+                        transcript.push_str(&line);
+                        transcript.push('\n');
+                    }
+                    "250 Queued"
+                } else if command == "QUIT" {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    "250 OK"
+                };
+                writer.write_all(format!("{}\r\n", reply).as_bytes()).await.unwrap();
+            }
+            transcript
+        });
+        (port, session)
+    }
+
+    fn config(port: u16) -> EmailConfig {
+        EmailConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            security: SmtpSecurity::None,
+            from: "Weather Agent <agent@example.com>".to_string(),
+            to: vec!["oncall@example.com".to_string()],
+            username_env: None,
+            password_env: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_plain_smtp_mail() {
+        let (port, session) = smtp_server("250 OK").await;
+        let sink = EmailSink::new("mail", config(port)).unwrap();
+        let notification = Notification::new("Tokyo", "Weather alerts for Tokyo: 1 new", "Tokyo: new heat alert");
+
+        sink.send(&notification).await.unwrap();
+
+        let transcript = session.await.unwrap();
+        assert!(transcript.contains("MAIL FROM:<agent@example.com>"), "{}", transcript);
+        assert!(transcript.contains("RCPT TO:<oncall@example.com>"), "{}", transcript);
+        assert!(transcript.contains("Subject: Weather alerts for Tokyo: 1 new"), "{}", transcript);
+        assert!(transcript.contains("Tokyo: new heat alert"), "{}", transcript);
+    }
+
+    #[tokio::test]
+    async fn rejected_recipients_fail_the_delivery() {
+        let (port, _session) = smtp_server("550 No such user").await;
+        let sink = EmailSink::new("mail", config(port)).unwrap();
+
+        let error = sink.send(&Notification::new("Tokyo", "Title", "Body")).await.unwrap_err();
+
+        let message = format!("{:#}", error);
+        assert!(message.contains("Email sink mail failed"), "{}", message);
+        assert!(message.contains("No such user"), "{}", message);
+    }
+
+    #[test]
+    fn needs_a_recipient() {
+        let mut config = config(25);
+        config.to.clear();
+
+        let error = EmailSink::new("mail", config).err().unwrap();
+
+        assert_eq!(error.to_string(), "Email sink mail has no recipients");
+    }
+}
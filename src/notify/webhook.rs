@@ -0,0 +1,124 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use super::{Notification, NotificationSink};
+
+/// POSTs each notification as JSON to a URL.
+pub struct WebhookSink {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self { name: name.into(), url: url.into(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        post(&self.client, &self.url, notification)
+            .await
+            .with_context(|| format!("Webhook {} failed", self.name))
+    }
+}
+
+/// Posts to a Slack (or Mattermost, Rocket.Chat, ...) incoming webhook.
+pub struct SlackSink {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl SlackSink {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self { name: name.into(), url: url.into(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for SlackSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let body = serde_json::json!({
+            "text": format!("*{}*\n{}", notification.title, notification.body)
+        });
+        post(&self.client, &self.url, &body)
+            .await
+            .with_context(|| format!("Slack webhook {} failed", self.name))
+    }
+}
+
+async fn post(client: &reqwest::Client, url: &str, body: &impl serde::Serialize) -> Result<()> {
+    client
+        .post(url)
+        .header("User-Agent", "multi-tool-agent/1.0")
+        .json(body)
+        .send()
+        .await
+        .context("Failed to send request")?
+        .error_for_status()
+        .context("Endpoint returned an error")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubServer;
+
+    fn notification() -> Notification {
+        Notification::new("Tokyo", "Weather alerts for Tokyo: 1 new", "Tokyo: new heat alert")
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_the_notification_as_json() {
+        let server = StubServer::start(vec![(204, "")]).await;
+        let sink = WebhookSink::new("dashboard", format!("{}/hooks/weather", server.url));
+
+        sink.send(&notification()).await.unwrap();
+
+        let sent = &server.requests()[0];
+        assert_eq!(sent.method, "POST");
+        assert_eq!(sent.path, "/hooks/weather");
+        assert_eq!(sent.header("content-type"), Some("application/json"));
+        let body = sent.json();
+        assert_eq!(body["location"], "Tokyo");
+        assert_eq!(body["title"], "Weather alerts for Tokyo: 1 new");
+        assert_eq!(body["body"], "Tokyo: new heat alert");
+        assert_eq!(body["changes"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn slack_posts_a_text_message() {
+        let server = StubServer::start(vec![(200, "ok")]).await;
+        let sink = SlackSink::new("team", &server.url);
+
+        sink.send(&notification()).await.unwrap();
+
+        assert_eq!(
+            server.requests()[0].json(),
+            serde_json::json!({"text": "*Weather alerts for Tokyo: 1 new*\nTokyo: new heat alert"})
+        );
+    }
+
+    #[tokio::test]
+    async fn server_errors_fail_the_delivery() {
+        let server = StubServer::start(vec![(503, "")]).await;
+        let sink = WebhookSink::new("dashboard", &server.url);
+
+        let error = sink.send(&notification()).await.unwrap_err();
+
+        let message = format!("{:#}", error);
+        assert!(message.contains("Webhook dashboard failed"), "{}", message);
+        assert!(message.contains("503"), "{}", message);
+    }
+}
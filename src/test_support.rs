@@ -0,0 +1,143 @@
+//! Local stand-ins for the HTTP services the tools talk to.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use crate::LocationInfo;
+
+/// A request as the stand-in server received it.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query, e.g. `/v1/forecast?latitude=1`.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).expect("request body is JSON")
+    }
+}
+
+/// An HTTP server on a free localhost port. Each request gets the next
+/// canned `(status, body)` response; the last one is repeated.
+pub struct StubServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl StubServer {
+    pub async fn start(responses: Vec<(u16, &str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind stub server");
+        let url = format!("http://{}", listener.local_addr().expect("stub server address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut responses: VecDeque<(u16, String)> =
+            responses.into_iter().map(|(status, body)| (status, body.to_string())).collect();
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let Some(request) = read_request(&mut stream).await else {
+                    continue;
+                };
+                recorded.lock().unwrap().push(request);
+                let (status, body) = if responses.len() > 1 {
+                    responses.pop_front().unwrap()
+                } else {
+                    responses.front().cloned().unwrap_or((404, String::new()))
+                };
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < length {
+        let mut chunk = [0; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    Some(RecordedRequest { method, path, headers, body: String::from_utf8_lossy(&body).to_string() })
+}
+
+pub fn location(name: &str, latitude: f64, longitude: f64) -> LocationInfo {
+    LocationInfo {
+        name: name.to_string(),
+        latitude,
+        longitude,
+        country: None,
+        country_code: None,
+        admin1: None,
+    }
+}
+
+/// Tokyo with its country, the place most news tests search for.
+pub fn tokyo() -> LocationInfo {
+    LocationInfo {
+        country: Some("Japan".to_string()),
+        country_code: Some("JP".to_string()),
+        ..location("Tokyo", 35.68, 139.69)
+    }
+}
+
+/// A `file://` URL for a file under `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    format!("file://{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
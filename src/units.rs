@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindSpeedUnit {
+    #[default]
+    Kmh,
+    Ms,
+    Mph,
+    Kn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipitationUnit {
+    #[default]
+    Mm,
+    Inch,
+}
+
+impl TemperatureUnit {
+    /// Value of Open-Meteo's `temperature_unit` parameter.
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Celsius => "celsius",
+            Self::Fahrenheit => "fahrenheit",
+        }
+    }
+
+    /// Converts a value given in °C into this unit.
+    pub fn from_celsius(self, celsius: f64) -> f64 {
+        match self {
+            Self::Celsius => celsius,
+            Self::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+impl WindSpeedUnit {
+    /// Value of Open-Meteo's `wind_speed_unit` parameter.
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Kmh => "kmh",
+            Self::Ms => "ms",
+            Self::Mph => "mph",
+            Self::Kn => "kn",
+        }
+    }
+
+    /// Converts a value given in km/h into this unit.
+    pub fn from_kmh(self, kmh: f64) -> f64 {
+        match self {
+            Self::Kmh => kmh,
+            Self::Ms => kmh / 3.6,
+            Self::Mph => kmh / 1.609_344,
+            Self::Kn => kmh / 1.852,
+        }
+    }
+}
+
+impl PrecipitationUnit {
+    /// Value of Open-Meteo's `precipitation_unit` parameter.
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Mm => "mm",
+            Self::Inch => "inch",
+        }
+    }
+
+    /// Converts a value given in mm into this unit.
+    pub fn from_mm(self, mm: f64) -> f64 {
+        match self {
+            Self::Mm => mm,
+            Self::Inch => mm / 25.4,
+        }
+    }
+}
+
+/// Measurement units requested from Open-Meteo. Rendered output uses the
+/// unit labels Open-Meteo echoes back, so it follows these automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub wind_speed: WindSpeedUnit,
+    pub precipitation: PrecipitationUnit,
+}
+
+impl Units {
+    /// °C, km/h, mm.
+    pub fn metric() -> Self {
+        Self::default()
+    }
+
+    /// °F, mph, inch.
+    pub fn imperial() -> Self {
+        Self {
+            temperature: TemperatureUnit::Fahrenheit,
+            wind_speed: WindSpeedUnit::Mph,
+            precipitation: PrecipitationUnit::Inch,
+        }
+    }
+
+    /// Query-string fragment (with leading `&`) for Open-Meteo endpoints.
+    pub fn query_params(&self) -> String {
+        format!(
+            "&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}",
+            self.temperature.as_param(),
+            self.wind_speed.as_param(),
+            self.precipitation.as_param()
+        )
+    }
+}
+
+impl FromStr for Units {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "metric" => Ok(Self::metric()),
+            "imperial" | "us" => Ok(Self::imperial()),
+            other => Err(anyhow::anyhow!("Unknown unit system '{}'. Use metric or imperial", other)),
+        }
+    }
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "celsius" | "c" => Ok(Self::Celsius),
+            "fahrenheit" | "f" => Ok(Self::Fahrenheit),
+            other => Err(anyhow::anyhow!("Unknown temperature unit '{}'. Use celsius or fahrenheit", other)),
+        }
+    }
+}
+
+impl FromStr for WindSpeedUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "kmh" | "km/h" => Ok(Self::Kmh),
+            "ms" | "m/s" => Ok(Self::Ms),
+            "mph" => Ok(Self::Mph),
+            "kn" | "knots" => Ok(Self::Kn),
+            other => Err(anyhow::anyhow!("Unknown wind speed unit '{}'. Use kmh, ms, mph or kn", other)),
+        }
+    }
+}
+
+impl FromStr for PrecipitationUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mm" => Ok(Self::Mm),
+            "inch" | "in" => Ok(Self::Inch),
+            other => Err(anyhow::anyhow!("Unknown precipitation unit '{}'. Use mm or inch", other)),
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}",
+            self.temperature.as_param(),
+            self.wind_speed.as_param(),
+            self.precipitation.as_param()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-9
+    }
+
+    #[test]
+    fn query_params_name_every_unit() {
+        assert_eq!(
+            Units::metric().query_params(),
+            "&temperature_unit=celsius&wind_speed_unit=kmh&precipitation_unit=mm"
+        );
+        assert_eq!(
+            Units::imperial().query_params(),
+            "&temperature_unit=fahrenheit&wind_speed_unit=mph&precipitation_unit=inch"
+        );
+        let mixed = Units { wind_speed: WindSpeedUnit::Kn, ..Units::metric() };
+        assert_eq!(mixed.to_string(), "celsius, kn, mm");
+    }
+
+    #[test]
+    fn parses_unit_systems_and_single_units() {
+        assert_eq!("Metric".parse::<Units>().unwrap(), Units::metric());
+        assert_eq!("US".parse::<Units>().unwrap(), Units::imperial());
+        assert_eq!("F".parse::<TemperatureUnit>().unwrap(), TemperatureUnit::Fahrenheit);
+        assert_eq!("celsius".parse::<TemperatureUnit>().unwrap(), TemperatureUnit::Celsius);
+        assert_eq!("m/s".parse::<WindSpeedUnit>().unwrap(), WindSpeedUnit::Ms);
+        assert_eq!("Knots".parse::<WindSpeedUnit>().unwrap(), WindSpeedUnit::Kn);
+        assert_eq!("in".parse::<PrecipitationUnit>().unwrap(), PrecipitationUnit::Inch);
+    }
+
+    #[test]
+    fn rejects_unknown_units() {
+        assert_eq!(
+            "kelvin".parse::<TemperatureUnit>().unwrap_err().to_string(),
+            "Unknown temperature unit 'kelvin'. Use celsius or fahrenheit"
+        );
+        assert_eq!(
+            "Beaufort".parse::<WindSpeedUnit>().unwrap_err().to_string(),
+            "Unknown wind speed unit 'beaufort'. Use kmh, ms, mph or kn"
+        );
+        assert!("cm".parse::<PrecipitationUnit>().is_err());
+        assert!("si".parse::<Units>().is_err());
+    }
+
+    #[test]
+    fn converts_from_metric() {
+        assert!(close(TemperatureUnit::Fahrenheit.from_celsius(35.0), 95.0));
+        assert!(close(TemperatureUnit::Fahrenheit.from_celsius(-40.0), -40.0));
+        assert!(close(TemperatureUnit::Celsius.from_celsius(-10.0), -10.0));
+        assert!(close(WindSpeedUnit::Ms.from_kmh(36.0), 10.0));
+        assert!(close(WindSpeedUnit::Mph.from_kmh(160.9344), 100.0));
+        assert!(close(WindSpeedUnit::Kn.from_kmh(92.6), 50.0));
+        assert!(close(WindSpeedUnit::Kmh.from_kmh(60.0), 60.0));
+        assert!(close(PrecipitationUnit::Inch.from_mm(50.8), 2.0));
+        assert!(close(PrecipitationUnit::Mm.from_mm(20.0), 20.0));
+    }
+
+    #[test]
+    fn config_tables_may_set_single_units() {
+        let units: Units = toml::from_str("wind_speed = \"kn\"").unwrap();
+
+        assert_eq!(units, Units { wind_speed: WindSpeedUnit::Kn, ..Units::default() });
+    }
+}
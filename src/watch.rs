@@ -0,0 +1,400 @@
+use anyhow::{Result, Context};
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::{
+    LocationInfo,
+    alerts::{Alert, AlertKind, Alerts, AlertsConfig, Severity},
+    notify::{Notification, NotificationSink},
+    units::Units,
+};
+
+const DEFAULT_INTERVAL_MINUTES: u64 = 30;
+const DEFAULT_STATE_FILE: &str = "watch-state.json";
+
+/// `[watch]` section of the config file.
+///
+/// ```toml
+/// [watch]
+/// interval_minutes = 15
+/// state_file = "/var/lib/agent/watch-state.json"
+/// quiet_hours = { start = "22:00", end = "07:00" }
+///
+/// [[watch.locations]]
+/// name = "Tokyo"
+/// country = "JP"
+/// notify = ["team"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    pub interval_minutes: u64,
+    pub state_file: PathBuf,
+    pub quiet_hours: Option<QuietHours>,
+    pub locations: Vec<WatchLocation>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+            state_file: PathBuf::from(DEFAULT_STATE_FILE),
+            quiet_hours: None,
+            locations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchLocation {
+    pub name: String,
+    /// Country code passed to geocoding to disambiguate the name.
+    #[serde(default)]
+    pub country: Option<String>,
+    /// Names of `[sinks]` entries that receive this location's changes.
+    #[serde(default)]
+    pub notify: Vec<String>,
+}
+
+/// Local-time window in which no polls run. Changes that happen meanwhile
+/// are reported by the first poll after it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Windows with `start` after `end` wrap past midnight.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChangeKind {
+    New,
+    Escalated { from: Severity },
+    Cleared,
+}
+
+/// Something worth telling the user about one location's alerts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertChange {
+    pub location: String,
+    #[serde(flatten)]
+    pub change: ChangeKind,
+    pub alert: Alert,
+}
+
+impl fmt::Display for AlertChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.change {
+            ChangeKind::New => write!(f, "{}: new {}", self.location, self.alert),
+            ChangeKind::Escalated { from } => write!(f, "{}: escalated from {} {}", self.location, from, self.alert),
+            ChangeKind::Cleared => write!(f, "{}: cleared {}", self.location, self.alert),
+        }
+    }
+}
+
+/// Alerts last reported per location, persisted between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchState {
+    /// Keyed by [`WatchState::key`].
+    pub locations: BTreeMap<String, Vec<Alert>>,
+}
+
+impl WatchState {
+    /// The name alone is ambiguous (Springfield, US is several places), so
+    /// the rounded coordinates are part of the key.
+    pub fn key(location_info: &LocationInfo) -> String {
+        format!("{} ({:.2}, {:.2})", location_info.name, location_info.latitude, location_info.longitude)
+    }
+
+    /// Loads the state file, starting empty when it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse watch state {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read watch state {}", path.display())),
+        }
+    }
+
+    /// Writes through a temporary file so a crash never leaves half a state.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let temporary = path.with_extension("tmp");
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize watch state")?;
+        std::fs::write(&temporary, contents)
+            .with_context(|| format!("Failed to write watch state {}", temporary.display()))?;
+        std::fs::rename(&temporary, path)
+            .with_context(|| format!("Failed to replace watch state {}", path.display()))
+    }
+}
+
+/// Compares the alerts reported last time with the current ones.
+///
+/// Alerts are matched by kind, source and overlapping period rather than
+/// exact dates, so a multi-day alert whose first day has passed, or whose
+/// end moved by a day, is still the same alert. Downgrades are not reported.
+pub fn diff(location: &str, previous: &[Alert], current: &[Alert]) -> Vec<AlertChange> {
+    let change = |change, alert: &Alert| AlertChange { location: location.to_string(), change, alert: alert.clone() };
+    let mut changes = Vec::new();
+
+    for alert in current {
+        match previous.iter().find(|old| same_alert(old, alert)) {
+            None => changes.push(change(ChangeKind::New, alert)),
+            Some(old) if alert.severity > old.severity => {
+                changes.push(change(ChangeKind::Escalated { from: old.severity }, alert));
+            }
+            Some(_) => {}
+        }
+    }
+    for old in previous {
+        if !current.iter().any(|alert| same_alert(old, alert)) {
+            changes.push(change(ChangeKind::Cleared, old));
+        }
+    }
+
+    changes
+}
+
+fn same_alert(a: &Alert, b: &Alert) -> bool {
+    // Published warnings are re-issued with new headlines; the event name
+    // before the colon is what identifies them.
+    let event = |alert: &Alert| alert.message.split(':').next().unwrap_or_default().to_string();
+    let date = |time: &str| time.get(..10).unwrap_or(time).to_string();
+
+    a.kind == b.kind
+        && a.source == b.source
+        && (a.kind != AlertKind::Official || event(a) == event(b))
+        && date(&a.start) <= date(&b.end)
+        && date(&b.start) <= date(&a.end)
+}
+
+/// A watched location and the sinks its changes go to.
+pub struct WatchTarget {
+    pub location_info: LocationInfo,
+    pub sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+/// Polls alerts for a fixed set of locations and reports what changed.
+pub struct Watcher {
+    targets: Vec<WatchTarget>,
+    units: Units,
+    alerts: AlertsConfig,
+    config: WatchConfig,
+    state: WatchState,
+}
+
+impl Watcher {
+    pub fn new(targets: Vec<WatchTarget>, units: Units, alerts: AlertsConfig, config: WatchConfig) -> Result<Self> {
+        let state = WatchState::load(&config.state_file)?;
+        Ok(Self { targets, units, alerts, config, state })
+    }
+
+    /// Checks every location once, records the new state and returns the
+    /// changes, one list per target in order. A location that fails to load
+    /// keeps its previous state so an outage is not mistaken for all its
+    /// alerts clearing.
+    pub async fn poll(&mut self) -> Result<Vec<Vec<AlertChange>>> {
+        let mut changes = Vec::new();
+
+        for WatchTarget { location_info, .. } in &self.targets {
+            let alerts = Alerts::new(location_info.clone())
+                .with_units(self.units)
+                .with_config(self.alerts.clone());
+            let current = match alerts.get_alert_list().await {
+                Ok(current) => current,
+                Err(e) => {
+                    tracing::warn!("Failed to check alerts for {}: {:#}", location_info.name, e);
+                    changes.push(Vec::new());
+                    continue;
+                }
+            };
+
+            let key = WatchState::key(location_info);
+            // State files from before the coordinates were part of the key
+            // use the bare name.
+            let previous = match self.state.locations.remove(&key) {
+                Some(previous) => previous,
+                None => self.state.locations.remove(&location_info.name).unwrap_or_default(),
+            };
+            changes.push(diff(&location_info.name, &previous, &current));
+            self.state.locations.insert(key, current);
+        }
+
+        self.state.save(&self.config.state_file)?;
+        Ok(changes)
+    }
+
+    /// Polls until interrupted with Ctrl-C, or just once when `once` is set.
+    /// Every change is printed; each location's changes are also sent as one
+    /// notification to its sinks.
+    pub async fn run(&mut self, once: bool) -> Result<()> {
+        let interval = Duration::from_secs(self.config.interval_minutes.max(1) * 60);
+
+        loop {
+            let quiet = self.config.quiet_hours.is_some_and(|quiet| quiet.contains(Local::now().time()));
+            if quiet {
+                tracing::debug!("Quiet hours, skipping poll");
+            } else {
+                let changes = self.poll().await?;
+                for change in changes.iter().flatten() {
+                    println!("{}", change);
+                }
+                self.notify(changes).await;
+            }
+
+            if once {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+
+    /// Delivery failures are logged; the changes are already recorded, so
+    /// they are not retried.
+    async fn notify(&self, changes: Vec<Vec<AlertChange>>) {
+        for (target, changes) in self.targets.iter().zip(changes) {
+            let name = &target.location_info.name;
+            if changes.is_empty() || target.sinks.is_empty() {
+                continue;
+            }
+
+            let notification = Notification::from_changes(name, changes);
+            for sink in &target.sinks {
+                if let Err(e) = sink.send(&notification).await {
+                    tracing::warn!("Failed to notify {} via {}: {:#}", name, sink.name(), e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::location;
+
+    fn alert(kind: AlertKind, severity: Severity, start: &str, end: &str, message: &str) -> Alert {
+        Alert {
+            severity,
+            kind,
+            start: start.to_string(),
+            end: end.to_string(),
+            message: message.to_string(),
+            source: "Test".to_string(),
+        }
+    }
+
+    fn heat(severity: Severity, start: &str, end: &str) -> Alert {
+        alert(AlertKind::Heat, severity, start, end, "Highs up to 33°C")
+    }
+
+    fn time(text: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(text, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn reports_new_escalated_and_cleared_alerts() {
+        let previous = [
+            heat(Severity::Moderate, "2025-07-01", "2025-07-03"),
+            alert(AlertKind::Wind, Severity::Severe, "2025-07-01", "2025-07-01", "Gusts up to 95 km/h"),
+        ];
+        let current = [
+            heat(Severity::Severe, "2025-07-02", "2025-07-04"),
+            alert(AlertKind::Frost, Severity::Moderate, "2025-07-05", "2025-07-05", "Lows down to -1°C"),
+        ];
+
+        let changes = diff("Tokyo", &previous, &current);
+
+        let kinds: Vec<ChangeKind> = changes.iter().map(|change| change.change).collect();
+        assert_eq!(
+            kinds,
+            [ChangeKind::Escalated { from: Severity::Moderate }, ChangeKind::New, ChangeKind::Cleared]
+        );
+        assert_eq!(changes[0].alert, current[0]);
+        assert_eq!(changes[2].alert, previous[1]);
+        assert!(changes.iter().all(|change| change.location == "Tokyo"));
+    }
+
+    #[test]
+    fn unchanged_and_downgraded_alerts_are_quiet() {
+        let previous = [heat(Severity::Severe, "2025-07-01", "2025-07-03")];
+        let current = [heat(Severity::Moderate, "2025-07-02", "2025-07-03")];
+
+        assert!(diff("Tokyo", &previous, &previous).is_empty());
+        assert!(diff("Tokyo", &previous, &current).is_empty());
+    }
+
+    #[test]
+    fn same_alert_needs_overlapping_days() {
+        let monday = heat(Severity::Moderate, "2025-07-07", "2025-07-07");
+        let tuesday = heat(Severity::Moderate, "2025-07-08", "2025-07-08");
+        let week = heat(Severity::Moderate, "2025-07-07", "2025-07-11");
+
+        assert!(same_alert(&monday, &week));
+        assert!(same_alert(&week, &tuesday));
+        assert!(!same_alert(&monday, &tuesday));
+    }
+
+    #[test]
+    fn same_alert_compares_kind_source_and_official_event() {
+        let warning = |message: &str| {
+            alert(AlertKind::Official, Severity::Severe, "2025-07-01T06:00:00-05:00", "2025-07-01T18:00:00-05:00", message)
+        };
+        let heat_warning = warning("Excessive Heat Warning: until 8 PM");
+        let reissued = warning("Excessive Heat Warning: extended until 10 PM");
+        let mut other_feed = reissued.clone();
+        other_feed.source = "MeteoAlarm".to_string();
+
+        assert!(same_alert(&heat_warning, &reissued));
+        assert!(!same_alert(&heat_warning, &warning("Flood Watch: until 8 PM")));
+        assert!(!same_alert(&heat_warning, &other_feed));
+        assert!(!same_alert(&heat(Severity::Severe, "2025-07-01", "2025-07-01"), &heat_warning));
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let night = QuietHours { start: time("22:00"), end: time("07:00") };
+
+        assert!(night.contains(time("22:00")));
+        assert!(night.contains(time("23:59")));
+        assert!(night.contains(time("00:00")));
+        assert!(night.contains(time("06:59")));
+        assert!(!night.contains(time("07:00")));
+        assert!(!night.contains(time("12:00")));
+        assert!(!night.contains(time("21:59")));
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let lunch = QuietHours { start: time("12:00"), end: time("13:00") };
+
+        assert!(lunch.contains(time("12:30")));
+        assert!(!lunch.contains(time("13:00")));
+        assert!(!lunch.contains(time("00:00")));
+    }
+
+    #[test]
+    fn state_keys_tell_same_named_places_apart() {
+        let illinois = location("Springfield", 39.80, -89.64);
+        let missouri = location("Springfield", 37.21, -93.29);
+
+        assert_ne!(WatchState::key(&illinois), WatchState::key(&missouri));
+        assert_eq!(WatchState::key(&illinois), "Springfield (39.80, -89.64)");
+    }
+}
@@ -1,21 +1,319 @@
 use anyhow::{Result, Context};
-use crate::{LocationInfo, WEATHER_API};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::{LocationInfo, WEATHER_API, llm::Tool, units::Units};
+
+pub mod format;
+
+pub use format::{describe_weather_code, render_forecast};
+
+const DEFAULT_FORECAST_DAYS: u8 = 3;
+const MAX_FORECAST_DAYS: u8 = 16;
+
+/// Open-Meteo `/v1/forecast` response.
+///
+/// Hourly and daily series are parallel arrays indexed like `time`; values are
+/// `None` where Open-Meteo has no data. Units are keyed by variable name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Forecast {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: Option<String>,
+    pub elevation: Option<f64>,
+    pub current_weather: Option<CurrentWeather>,
+    #[serde(default)]
+    pub current_weather_units: BTreeMap<String, String>,
+    pub hourly: Option<HourlyForecast>,
+    #[serde(default)]
+    pub hourly_units: BTreeMap<String, String>,
+    pub daily: Option<DailyForecast>,
+    #[serde(default)]
+    pub daily_units: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentWeather {
+    pub time: String,
+    pub temperature: f64,
+    pub windspeed: f64,
+    pub winddirection: f64,
+    /// WMO weather interpretation code.
+    pub weathercode: u8,
+    pub is_day: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HourlyForecast {
+    pub time: Vec<String>,
+    #[serde(default)]
+    pub temperature_2m: Vec<Option<f64>>,
+    #[serde(default)]
+    pub relative_humidity_2m: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wind_speed_10m: Vec<Option<f64>>,
+    #[serde(default)]
+    pub apparent_temperature: Vec<Option<f64>>,
+    #[serde(default)]
+    pub precipitation_probability: Vec<Option<f64>>,
+    #[serde(default)]
+    pub uv_index: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wind_gusts_10m: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyForecast {
+    pub time: Vec<String>,
+    #[serde(default)]
+    pub weather_code: Vec<Option<u8>>,
+    #[serde(default)]
+    pub temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    pub precipitation_sum: Vec<Option<f64>>,
+    #[serde(default)]
+    pub apparent_temperature_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub apparent_temperature_min: Vec<Option<f64>>,
+    #[serde(default)]
+    pub precipitation_probability_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub uv_index_max: Vec<Option<f64>>,
+    #[serde(default)]
+    pub wind_gusts_10m_max: Vec<Option<f64>>,
+    /// Local ISO timestamps.
+    #[serde(default)]
+    pub sunrise: Vec<Option<String>>,
+    #[serde(default)]
+    pub sunset: Vec<Option<String>>,
+}
+
+impl Forecast {
+    /// Unit Open-Meteo reported for a daily variable, or an empty string.
+    pub fn daily_unit(&self, variable: &str) -> &str {
+        self.daily_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Unit Open-Meteo reported for an hourly variable, or an empty string.
+    pub fn hourly_unit(&self, variable: &str) -> &str {
+        self.hourly_units.get(variable).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Unit Open-Meteo reported for a `current_weather` field, or an empty string.
+    pub fn current_unit(&self, field: &str) -> &str {
+        self.current_weather_units.get(field).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Which forecast series to request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForecastDetail {
+    Hourly,
+    Daily,
+    #[default]
+    Both,
+}
+
+/// Optional variables on top of the default temperature, humidity, wind and
+/// precipitation series. Each maps to the matching hourly and/or daily
+/// Open-Meteo variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForecastVariable {
+    ApparentTemperature,
+    PrecipitationProbability,
+    UvIndex,
+    WindGusts,
+    Sunrise,
+    Sunset,
+}
+
+impl ForecastVariable {
+    pub const ALL: [ForecastVariable; 6] = [
+        Self::ApparentTemperature,
+        Self::PrecipitationProbability,
+        Self::UvIndex,
+        Self::WindGusts,
+        Self::Sunrise,
+        Self::Sunset,
+    ];
+
+    /// Name used in the tool schema.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ApparentTemperature => "apparent_temperature",
+            Self::PrecipitationProbability => "precipitation_probability",
+            Self::UvIndex => "uv_index",
+            Self::WindGusts => "wind_gusts",
+            Self::Sunrise => "sunrise",
+            Self::Sunset => "sunset",
+        }
+    }
+
+    /// Accepts schema names as well as Open-Meteo's own variable names.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "apparent_temperature" | "apparent_temperature_max" | "apparent_temperature_min" => Some(Self::ApparentTemperature),
+            "precipitation_probability" | "precipitation_probability_max" => Some(Self::PrecipitationProbability),
+            "uv_index" | "uv_index_max" => Some(Self::UvIndex),
+            "wind_gusts" | "wind_gusts_10m" | "wind_gusts_10m_max" => Some(Self::WindGusts),
+            "sunrise" => Some(Self::Sunrise),
+            "sunset" => Some(Self::Sunset),
+            _ => None,
+        }
+    }
+
+    fn hourly_params(self) -> &'static [&'static str] {
+        match self {
+            Self::ApparentTemperature => &["apparent_temperature"],
+            Self::PrecipitationProbability => &["precipitation_probability"],
+            Self::UvIndex => &["uv_index"],
+            Self::WindGusts => &["wind_gusts_10m"],
+            Self::Sunrise | Self::Sunset => &[],
+        }
+    }
+
+    fn daily_params(self) -> &'static [&'static str] {
+        match self {
+            Self::ApparentTemperature => &["apparent_temperature_max", "apparent_temperature_min"],
+            Self::PrecipitationProbability => &["precipitation_probability_max"],
+            Self::UvIndex => &["uv_index_max"],
+            Self::WindGusts => &["wind_gusts_10m_max"],
+            Self::Sunrise => &["sunrise"],
+            Self::Sunset => &["sunset"],
+        }
+    }
+}
+
+/// Parameters of a forecast request, also what the weather tool accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForecastRequest {
+    /// Forecast horizon in days, clamped to Open-Meteo's 1–16.
+    pub days: u8,
+    pub detail: ForecastDetail,
+    pub variables: Vec<ForecastVariable>,
+}
+
+impl Default for ForecastRequest {
+    fn default() -> Self {
+        Self::days(DEFAULT_FORECAST_DAYS)
+    }
+}
+
+impl ForecastRequest {
+    pub fn days(days: u8) -> Self {
+        Self { days, detail: ForecastDetail::default(), variables: Vec::new() }
+    }
+
+    /// Reads the weather tool arguments, ignoring unknown variables.
+    pub fn from_args(args: &serde_json::Value) -> Self {
+        let days = args["days"]
+            .as_u64()
+            .map(|days| days.clamp(1, u64::from(MAX_FORECAST_DAYS)) as u8)
+            .unwrap_or(DEFAULT_FORECAST_DAYS);
+        let detail = match args["detail"].as_str() {
+            Some("hourly") => ForecastDetail::Hourly,
+            Some("daily") => ForecastDetail::Daily,
+            _ => ForecastDetail::Both,
+        };
+        let mut variables = Vec::new();
+        for variable in args["variables"].as_array().into_iter().flatten().filter_map(|name| name.as_str()) {
+            match ForecastVariable::from_name(variable) {
+                Some(variable) if !variables.contains(&variable) => variables.push(variable),
+                Some(_) => {}
+                None => tracing::debug!("Ignoring unknown forecast variable {}", variable),
+            }
+        }
+        Self { days, detail, variables }
+    }
+
+    /// Days actually requested from Open-Meteo once `days` is clamped.
+    pub fn forecast_days(&self) -> u8 {
+        self.days.clamp(1, MAX_FORECAST_DAYS)
+    }
+
+    fn query_params(&self) -> String {
+        let mut params = format!("&forecast_days={}", self.forecast_days());
+
+        if self.detail != ForecastDetail::Daily {
+            let hourly: Vec<&str> = ["temperature_2m", "relative_humidity_2m", "wind_speed_10m"]
+                .into_iter()
+                .chain(self.variables.iter().flat_map(|variable| variable.hourly_params().iter().copied()))
+                .collect();
+            params.push_str(&format!("&hourly={}", hourly.join(",")));
+        }
+        if self.detail != ForecastDetail::Hourly {
+            let daily: Vec<&str> = ["weather_code", "temperature_2m_max", "temperature_2m_min", "precipitation_sum"]
+                .into_iter()
+                .chain(self.variables.iter().flat_map(|variable| variable.daily_params().iter().copied()))
+                .collect();
+            params.push_str(&format!("&daily={}", daily.join(",")));
+        }
+
+        params
+    }
+}
+
+/// Error body Open-Meteo returns with a 4xx status.
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenMeteoError {
+    pub reason: String,
+}
 
 pub struct Weather {
     location_info: LocationInfo,
+    units: Units,
+    base_url: String,
 }
 
 impl Weather {
     pub fn new(location_info: LocationInfo) -> Self {
-        Self { location_info }
+        Self { location_info, units: Units::default(), base_url: WEATHER_API.to_string() }
+    }
+
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Requests forecasts from another `/v1/forecast` URL, such as a
+    /// self-hosted Open-Meteo instance.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
     }
 
     pub async fn get_weather(&self) -> Result<String> {
+        self.get_weather_with(&ForecastRequest::default()).await
+    }
+
+    /// Current weather plus a forecast of `days` days (clamped to Open-Meteo's 1–16).
+    pub async fn get_weather_for_days(&self, days: u8) -> Result<String> {
+        self.get_weather_with(&ForecastRequest::days(days)).await
+    }
+
+    pub async fn get_weather_with(&self, request: &ForecastRequest) -> Result<String> {
+        let forecast = self.get_forecast_with(request).await?;
+        Ok(render_forecast(&self.location_info, &forecast))
+    }
+
+    pub async fn get_forecast(&self) -> Result<Forecast> {
+        self.get_forecast_with(&ForecastRequest::default()).await
+    }
+
+    pub async fn get_forecast_for_days(&self, days: u8) -> Result<Forecast> {
+        self.get_forecast_with(&ForecastRequest::days(days)).await
+    }
+
+    pub async fn get_forecast_with(&self, request: &ForecastRequest) -> Result<Forecast> {
         let url = format!(
-            "{base}?latitude={lat}&longitude={lon}&current_weather=true&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m&daily=temperature_2m_max,temperature_2m_min,precipitation_sum&timezone=auto&forecast_days=3",
-            base = WEATHER_API, 
-            lat = self.location_info.latitude, 
-            lon = self.location_info.longitude
+            "{base}?latitude={lat}&longitude={lon}&current_weather=true&timezone=auto{request}{units}",
+            base = self.base_url,
+            lat = self.location_info.latitude,
+            lon = self.location_info.longitude,
+            request = request.query_params(),
+            units = self.units.query_params()
         );
         
         let client = reqwest::Client::new();
@@ -25,16 +323,168 @@ impl Weather {
             .send()
             .await
             .context("Failed to fetch weather data")?;
+
+        if !response.status().is_success() {
+            let error: OpenMeteoError = response
+                .json()
+                .await
+                .context("Failed to read weather error response")?;
+            anyhow::bail!("Open-Meteo rejected the forecast request: {}", error.reason);
+        }
         
-        let weather_data = response
-            .text()
+        response
+            .json()
             .await
-            .context("Failed to read weather response")?;
-        
-        Ok(format!("Current weather and 3-day forecast for {} ({}°N, {}°E): {}", 
-                  self.location_info.name, 
-                  self.location_info.latitude, 
-                  self.location_info.longitude,
-                  weather_data))
+            .context("Failed to parse weather response")
+    }
+}
+
+#[async_trait]
+impl Tool for Weather {
+    fn name(&self) -> &str {
+        "get_weather"
+    }
+
+    fn description(&self) -> &str {
+        "Get current weather plus an hourly and/or daily forecast (3 days unless the user asks for more). \
+        Request extra variables only when the question needs them."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let variables: Vec<&str> = ForecastVariable::ALL.iter().map(|variable| variable.name()).collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "days": {
+                    "type": "integer",
+                    "description": "Number of forecast days, 1 to 16"
+                },
+                "detail": {
+                    "type": "string",
+                    "enum": ["hourly", "daily", "both"],
+                    "description": "Hourly series, daily summaries, or both (default)"
+                },
+                "variables": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": variables },
+                    "description": "Extra variables: feels-like temperature, chance of rain, UV index, wind gusts, sunrise, sunset"
+                }
+            }
+        })
+    }
+
+    async fn call(&self, args: serde_json::Value) -> Result<String> {
+        self.get_weather_with(&ForecastRequest::from_args(&args)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{StubServer, location};
+
+    const FORECAST: &str = r#"{
+        "latitude": 39.74, "longitude": -104.99, "timezone": "America/Denver", "elevation": 1609.0,
+        "current_weather": {"time": "2025-06-15T09:00", "temperature": 71.2, "windspeed": 8.3, "winddirection": 225, "weathercode": 2, "is_day": 1},
+        "current_weather_units": {"time": "iso8601", "temperature": "°F", "windspeed": "mp/h", "winddirection": "°"},
+        "hourly": {
+            "time": ["2025-06-15T09:00", "2025-06-15T10:00"],
+            "temperature_2m": [71.2, null],
+            "relative_humidity_2m": [30, 28],
+            "wind_speed_10m": [8.3, 9.1],
+            "uv_index": [5.1, 6.4]
+        },
+        "hourly_units": {"temperature_2m": "°F", "relative_humidity_2m": "%", "wind_speed_10m": "mp/h", "uv_index": ""},
+        "daily": {
+            "time": ["2025-06-15"],
+            "weather_code": [95],
+            "temperature_2m_max": [88.5],
+            "temperature_2m_min": [58.1],
+            "precipitation_sum": [0.12],
+            "sunrise": ["2025-06-15T05:31"]
+        },
+        "daily_units": {"temperature_2m_max": "°F", "precipitation_sum": "inch", "sunrise": "iso8601"}
+    }"#;
+
+    #[test]
+    fn clamps_days_and_reads_tool_arguments() {
+        let request = ForecastRequest::from_args(&serde_json::json!({
+            "days": 40,
+            "detail": "daily",
+            "variables": ["wind_gusts", "wind_gusts_10m_max", "sunrise", "snowfall"]
+        }));
+
+        assert_eq!(request.days, 16);
+        assert_eq!(request.detail, ForecastDetail::Daily);
+        assert_eq!(request.variables, [ForecastVariable::WindGusts, ForecastVariable::Sunrise]);
+        assert_eq!(ForecastRequest::from_args(&serde_json::json!({"days": 0})).days, 1);
+        assert_eq!(ForecastRequest::days(0).forecast_days(), 1);
+        assert_eq!(ForecastRequest::days(30).forecast_days(), 16);
+        assert_eq!(ForecastRequest::from_args(&serde_json::json!({})), ForecastRequest::default());
+    }
+
+    #[test]
+    fn requests_only_the_series_asked_for() {
+        let daily = ForecastRequest {
+            days: 2,
+            detail: ForecastDetail::Daily,
+            variables: vec![ForecastVariable::UvIndex, ForecastVariable::Sunset],
+        };
+        let hourly = ForecastRequest {
+            days: 40,
+            detail: ForecastDetail::Hourly,
+            variables: vec![ForecastVariable::UvIndex, ForecastVariable::Sunset],
+        };
+
+        assert_eq!(
+            daily.query_params(),
+            "&forecast_days=2&daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,uv_index_max,sunset"
+        );
+        assert_eq!(hourly.query_params(), "&forecast_days=16&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m,uv_index");
+        assert_eq!(
+            ForecastRequest::days(1).query_params(),
+            "&forecast_days=1&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m\
+            &daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum"
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_the_forecast_with_its_units() {
+        let server = StubServer::start(vec![(200, FORECAST)]).await;
+        let weather = Weather::new(location("Denver", 39.74, -104.99))
+            .with_units(Units::imperial())
+            .with_base_url(format!("{}/v1/forecast", server.url));
+        let request = ForecastRequest { variables: vec![ForecastVariable::UvIndex], ..ForecastRequest::days(1) };
+
+        let forecast = weather.get_forecast_with(&request).await.unwrap();
+
+        let current = forecast.current_weather.as_ref().unwrap();
+        assert_eq!(current.temperature, 71.2);
+        assert_eq!(current.weathercode, 2);
+        assert_eq!(forecast.current_unit("windspeed"), "mp/h");
+        let hourly = forecast.hourly.as_ref().unwrap();
+        assert_eq!(hourly.temperature_2m, [Some(71.2), None]);
+        assert_eq!(hourly.uv_index, [Some(5.1), Some(6.4)]);
+        assert!(hourly.wind_gusts_10m.is_empty());
+        let daily = forecast.daily.as_ref().unwrap();
+        assert_eq!(daily.weather_code, [Some(95)]);
+        assert_eq!(daily.sunrise, [Some("2025-06-15T05:31".to_string())]);
+        assert_eq!(forecast.daily_unit("precipitation_sum"), "inch");
+        assert_eq!(forecast.hourly_unit("missing"), "");
+
+        let path = &server.requests()[0].path;
+        assert!(path.starts_with("/v1/forecast?latitude=39.74&longitude=-104.99&current_weather=true"), "{}", path);
+        assert!(path.contains("&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m,uv_index"), "{}", path);
+        assert!(path.ends_with("&temperature_unit=fahrenheit&wind_speed_unit=mph&precipitation_unit=inch"), "{}", path);
+    }
+
+    #[tokio::test]
+    async fn reports_open_meteo_errors() {
+        let server = StubServer::start(vec![(400, r#"{"error": true, "reason": "Forecast days is invalid."}"#)]).await;
+        let weather = Weather::new(location("Denver", 39.74, -104.99)).with_base_url(&server.url);
+
+        let error = weather.get_forecast().await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Open-Meteo rejected the forecast request: Forecast days is invalid.");
     }
-} 
\ No newline at end of file
+}
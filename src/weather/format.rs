@@ -0,0 +1,358 @@
+use super::Forecast;
+use crate::LocationInfo;
+
+/// Hours covered by the hourly strip and the spacing between entries.
+const HOURLY_STRIP_HOURS: usize = 24;
+const HOURLY_STRIP_STEP: usize = 3;
+/// Hours shown, every hour, when only the hourly series was requested.
+const HOURLY_ONLY_HOURS: usize = 48;
+
+/// Plain-language description of a WMO weather interpretation code, as used
+/// by Open-Meteo's `weathercode` / `weather_code` fields.
+pub fn describe_weather_code(code: u8) -> &'static str {
+    match code {
+        0 => "clear sky",
+        1 => "mainly clear",
+        2 => "partly cloudy",
+        3 => "overcast",
+        45 => "fog",
+        48 => "depositing rime fog",
+        51 => "light drizzle",
+        53 => "moderate drizzle",
+        55 => "dense drizzle",
+        56 => "light freezing drizzle",
+        57 => "dense freezing drizzle",
+        61 => "slight rain",
+        63 => "moderate rain",
+        65 => "heavy rain",
+        66 => "light freezing rain",
+        67 => "heavy freezing rain",
+        71 => "slight snowfall",
+        73 => "moderate snowfall",
+        75 => "heavy snowfall",
+        77 => "snow grains",
+        80 => "slight rain showers",
+        81 => "moderate rain showers",
+        82 => "violent rain showers",
+        85 => "slight snow showers",
+        86 => "heavy snow showers",
+        95 => "thunderstorm",
+        96 => "thunderstorm with slight hail",
+        99 => "thunderstorm with heavy hail",
+        _ => "unknown conditions",
+    }
+}
+
+/// 16-point compass name for a bearing in degrees.
+pub fn compass_direction(degrees: f64) -> &'static str {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE",
+        "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+    ];
+    let index = (degrees.rem_euclid(360.0) / 22.5).round() as usize % POINTS.len();
+    POINTS[index]
+}
+
+/// Compact text rendering of a forecast: current conditions, a three-hourly
+/// strip for the next day and a daily table, all with units. This is what the
+/// weather tool hands to the LLM, and it reads fine on its own.
+pub fn render_forecast(location_info: &LocationInfo, forecast: &Forecast) -> String {
+    let mut output = format!(
+        "Weather for {} ({:.4}°N, {:.4}°E)",
+        location_info.name, location_info.latitude, location_info.longitude
+    );
+    if let Some(timezone) = &forecast.timezone {
+        output.push_str(&format!(", local time {}", timezone));
+    }
+    output.push('\n');
+
+    if let Some(current) = &forecast.current_weather {
+        output.push_str(&format!(
+            "Now ({}): {}, {}, wind {} from {}\n",
+            current.time.replace('T', " "),
+            describe_weather_code(current.weathercode),
+            format_value(Some(current.temperature), forecast.current_unit("temperature")),
+            format_value(Some(current.windspeed), forecast.current_unit("windspeed")),
+            compass_direction(current.winddirection)
+        ));
+    }
+
+    if let Some(hourly) = &forecast.hourly {
+        // Without daily summaries the hourly series is the whole answer, so
+        // show every hour for longer; otherwise a three-hourly day suffices.
+        let (hours, step) = if forecast.daily.is_some() {
+            (HOURLY_STRIP_HOURS, HOURLY_STRIP_STEP)
+        } else {
+            (HOURLY_ONLY_HOURS, 1)
+        };
+
+        // Start the strip at the hour containing the current observation.
+        let start = forecast.current_weather
+            .as_ref()
+            .map(|current| hourly.time.iter().filter(|time| time.as_str() <= current.time.as_str()).count())
+            .map_or(0, |elapsed| elapsed.saturating_sub(1));
+        let end = (start + hours).min(hourly.time.len());
+        let rows: Vec<usize> = (start..end).step_by(step).collect();
+
+        if !rows.is_empty() {
+            let numeric = |header, series: &[Option<f64>], variable| {
+                Column::numeric(header, series, &rows, forecast.hourly_unit(variable))
+            };
+            let columns = [
+                Column::text("Time", rows.iter().map(|&index| short_time(&hourly.time[index])).collect()),
+                numeric("Temp", &hourly.temperature_2m, "temperature_2m"),
+                numeric("Feels like", &hourly.apparent_temperature, "apparent_temperature"),
+                numeric("Humidity", &hourly.relative_humidity_2m, "relative_humidity_2m"),
+                numeric("Rain chance", &hourly.precipitation_probability, "precipitation_probability"),
+                numeric("Wind", &hourly.wind_speed_10m, "wind_speed_10m"),
+                numeric("Gusts", &hourly.wind_gusts_10m, "wind_gusts_10m"),
+                numeric("UV", &hourly.uv_index, "uv_index"),
+            ];
+
+            output.push_str(&format!("\nNext {} hours:\n", end - start));
+            output.push_str(&render_table(&columns));
+        }
+    }
+
+    if let Some(daily) = &forecast.daily {
+        let rows: Vec<usize> = (0..daily.time.len()).collect();
+        let numeric = |header, series: &[Option<f64>], variable| {
+            Column::numeric(header, series, &rows, forecast.daily_unit(variable))
+        };
+        let clock = |header, series: &[Option<String>]| {
+            let cells: Vec<String> = rows
+                .iter()
+                .map(|&index| match series.get(index).cloned().flatten() {
+                    Some(time) => time.rsplit('T').next().unwrap_or_default().to_string(),
+                    None => "n/a".to_string(),
+                })
+                .collect();
+            Column::optional_text(header, cells, !series.is_empty())
+        };
+        let columns = [
+            Column::text("Date", daily.time.clone()),
+            Column::text(
+                "Conditions",
+                rows.iter()
+                    .map(|&index| {
+                        daily.weather_code
+                            .get(index)
+                            .copied()
+                            .flatten()
+                            .map_or("n/a", describe_weather_code)
+                            .to_string()
+                    })
+                    .collect(),
+            ),
+            numeric("Min", &daily.temperature_2m_min, "temperature_2m_min"),
+            numeric("Max", &daily.temperature_2m_max, "temperature_2m_max"),
+            numeric("Feels min", &daily.apparent_temperature_min, "apparent_temperature_min"),
+            numeric("Feels max", &daily.apparent_temperature_max, "apparent_temperature_max"),
+            numeric("Precip", &daily.precipitation_sum, "precipitation_sum"),
+            numeric("Rain chance", &daily.precipitation_probability_max, "precipitation_probability_max"),
+            numeric("Gusts", &daily.wind_gusts_10m_max, "wind_gusts_10m_max"),
+            numeric("UV max", &daily.uv_index_max, "uv_index_max"),
+            clock("Sunrise", &daily.sunrise),
+            clock("Sunset", &daily.sunset),
+        ];
+
+        output.push_str(&format!("\n{}-day forecast:\n", daily.time.len()));
+        output.push_str(&render_table(&columns));
+    }
+
+    output
+}
+
+/// One table column. Columns for variables that were not requested are
+/// skipped entirely rather than printed as a row of `n/a`.
+pub(crate) struct Column {
+    header: &'static str,
+    cells: Vec<String>,
+    align_left: bool,
+    present: bool,
+}
+
+impl Column {
+    pub(crate) fn text(header: &'static str, cells: Vec<String>) -> Self {
+        Self { header, cells, align_left: true, present: true }
+    }
+
+    pub(crate) fn optional_text(header: &'static str, cells: Vec<String>, present: bool) -> Self {
+        Self { header, cells, align_left: false, present }
+    }
+
+    pub(crate) fn numeric(header: &'static str, series: &[Option<f64>], rows: &[usize], unit: &str) -> Self {
+        Self {
+            header,
+            cells: rows.iter().map(|&index| format_value(value_at(series, index), unit)).collect(),
+            align_left: false,
+            present: !series.is_empty(),
+        }
+    }
+}
+
+pub(crate) fn render_table(columns: &[Column]) -> String {
+    let columns: Vec<&Column> = columns.iter().filter(|column| column.present).collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|column| {
+            column.cells
+                .iter()
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(column.header.len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let rows = columns.iter().map(|column| column.cells.len()).max().unwrap_or_default();
+
+    let line = |cell: &dyn Fn(&Column) -> &str| {
+        let cells: Vec<String> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, &width)| {
+                let value = cell(column);
+                let padding = " ".repeat(width.saturating_sub(value.chars().count()));
+                if column.align_left { format!("{}{}", value, padding) } else { format!("{}{}", padding, value) }
+            })
+            .collect();
+        format!("  {}\n", cells.join("  ").trim_end())
+    };
+
+    let mut table = line(&|column| column.header);
+    for row in 0..rows {
+        table.push_str(&line(&|column| column.cells.get(row).map(String::as_str).unwrap_or_default()));
+    }
+    table
+}
+
+pub(crate) fn value_at(series: &[Option<f64>], index: usize) -> Option<f64> {
+    series.get(index).copied().flatten()
+}
+
+/// Drops the year from an ISO timestamp: `2024-06-01T15:00` -> `06-01 15:00`.
+fn short_time(time: &str) -> String {
+    time.get(5..).unwrap_or(time).replace('T', " ")
+}
+
+pub(crate) fn format_value(value: Option<f64>, unit: &str) -> String {
+    match value {
+        // Symbol units hug the number (21°C, 60%); word units get a space (5 km/h).
+        Some(value) if unit.starts_with(char::is_alphabetic) => format!("{} {}", value, unit),
+        Some(value) => format!("{}{}", value, unit),
+        None => "n/a".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::location;
+
+    fn forecast(json: serde_json::Value) -> Forecast {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn describes_weather_codes() {
+        assert_eq!(describe_weather_code(0), "clear sky");
+        assert_eq!(describe_weather_code(63), "moderate rain");
+        assert_eq!(describe_weather_code(99), "thunderstorm with heavy hail");
+        assert_eq!(describe_weather_code(42), "unknown conditions");
+    }
+
+    #[test]
+    fn names_the_nearest_compass_point() {
+        assert_eq!(compass_direction(0.0), "N");
+        assert_eq!(compass_direction(11.2), "N");
+        assert_eq!(compass_direction(11.3), "NNE");
+        assert_eq!(compass_direction(225.0), "SW");
+        assert_eq!(compass_direction(350.0), "N");
+        assert_eq!(compass_direction(360.0), "N");
+        assert_eq!(compass_direction(-90.0), "W");
+    }
+
+    #[test]
+    fn spaces_word_units_only() {
+        assert_eq!(format_value(Some(21.5), "°C"), "21.5°C");
+        assert_eq!(format_value(Some(60.0), "%"), "60%");
+        assert_eq!(format_value(Some(5.0), "km/h"), "5 km/h");
+        assert_eq!(format_value(Some(3.0), ""), "3");
+        assert_eq!(format_value(None, "°C"), "n/a");
+    }
+
+    #[test]
+    fn tables_skip_missing_columns_and_align_cells() {
+        let columns = [
+            Column::text("Day", vec!["Mon".to_string(), "Tuesday".to_string()]),
+            Column::numeric("Max", &[Some(9.5), None], &[0, 1], "°C"),
+            Column::numeric("UV", &[], &[0, 1], ""),
+            Column::optional_text("Sunset", vec!["n/a".to_string(), "n/a".to_string()], false),
+        ];
+
+        let table = render_table(&columns);
+
+        assert_eq!(table, "  Day        Max\n  Mon      9.5°C\n  Tuesday    n/a\n");
+    }
+
+    #[test]
+    fn renders_current_conditions_hourly_strip_and_daily_table() {
+        let hours: Vec<String> = (0..30).map(|hour| format!("2025-06-{:02}T{:02}:00", 15 + hour / 24, hour % 24)).collect();
+        let temperatures: Vec<f64> = (0..30).map(|hour| 10.0 + hour as f64).collect();
+        let forecast = forecast(serde_json::json!({
+            "latitude": 52.52, "longitude": 13.41, "timezone": "Europe/Berlin",
+            "current_weather": {"time": "2025-06-15T04:30", "temperature": 14.5, "windspeed": 11.0, "winddirection": 270, "weathercode": 61},
+            "current_weather_units": {"temperature": "°C", "windspeed": "km/h"},
+            "hourly": {"time": hours, "temperature_2m": temperatures},
+            "hourly_units": {"temperature_2m": "°C"},
+            "daily": {
+                "time": ["2025-06-15", "2025-06-16"],
+                "weather_code": [61, null],
+                "temperature_2m_max": [22.0, 25.5],
+                "temperature_2m_min": [12.0, 13.5],
+                "sunrise": ["2025-06-15T04:43", null]
+            },
+            "daily_units": {"temperature_2m_max": "°C", "temperature_2m_min": "°C"}
+        }));
+
+        let output = render_forecast(&location("Berlin", 52.52, 13.41), &forecast);
+
+        let expected = "\
+Weather for Berlin (52.5200°N, 13.4100°E), local time Europe/Berlin
+Now (2025-06-15 04:30): slight rain, 14.5°C, wind 11 km/h from W
+
+Next 24 hours:
+  Time         Temp
+  06-15 04:00  14°C
+  06-15 07:00  17°C
+  06-15 10:00  20°C
+  06-15 13:00  23°C
+  06-15 16:00  26°C
+  06-15 19:00  29°C
+  06-15 22:00  32°C
+  06-16 01:00  35°C
+
+2-day forecast:
+  Date        Conditions      Min     Max  Sunrise
+  2025-06-15  slight rain    12°C    22°C    04:43
+  2025-06-16  n/a          13.5°C  25.5°C      n/a
+";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn hourly_only_forecasts_list_every_hour() {
+        let hours: Vec<String> = (0..3).map(|hour| format!("2025-06-15T{:02}:00", hour)).collect();
+        let forecast = forecast(serde_json::json!({
+            "latitude": 52.52, "longitude": 13.41,
+            "hourly": {"time": hours, "temperature_2m": [10.0, 11.0, null], "wind_speed_10m": [5.0, 6.0, 7.0]},
+            "hourly_units": {"temperature_2m": "°C", "wind_speed_10m": "km/h"}
+        }));
+
+        let output = render_forecast(&location("Berlin", 52.52, 13.41), &forecast);
+
+        assert!(output.contains("Next 3 hours:\n"), "{}", output);
+        assert!(output.contains("  06-15 02:00   n/a  7 km/h\n"), "{}", output);
+        assert!(!output.contains("forecast:"), "{}", output);
+    }
+}